@@ -21,3 +21,16 @@ pub const MIN_SELL_AMOUNT: u64 = 1_000;      // 0.001 tokens
 
 // Price impact limits
 pub const MAX_PRICE_IMPACT_BPS: u64 = 1000;  // 10%
+
+// PDA that holds locked position NFTs, no instruction ever signs for it
+pub const LP_LOCK_SEED: &str = "lp_lock";
+
+// PDA that becomes the config authority under multisig approval, signs approved admin proposals
+pub const MULTISIG_SIGNER_SEED: &str = "multisig_signer";
+
+// Largest gap between the sqrt price of a pool created before migration and the curve's final
+// sqrt price for the migration to reuse that pool, in FEE_PRECISION units (1% of the sqrt price)
+pub const MAX_POOL_PRICE_DEVIATION_BPS: u64 = 100;
+
+// Shortest notice holders get between start_unwind and the first redemption, whatever the config timelock
+pub const MIN_UNWIND_DELAY: i64 = 86_400; // 1 day
@@ -88,4 +88,97 @@ pub enum SwifeyError {
 
     #[msg("User has insufficient SOL balance for this transaction")]
     InsufficientUserBalance,
+
+    #[msg("Curve template is inactive")]
+    CurveTemplateInactive,
+
+    #[msg("Curve template does not match the requested index")]
+    InvalidCurveTemplate,
+
+    #[msg("Curve cannot cover its outstanding tokens")]
+    CurveInsolvent,
+
+    #[msg("LP lock account is not the position NFT account of the LP lock")]
+    InvalidLpLockAccount,
+
+    #[msg("Curve migrates to a different pool type")]
+    InvalidMigrationTarget,
+
+    #[msg("Authority changes must go through propose_authority and accept_authority")]
+    AuthorityChangeNotAllowed,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Queued config change cannot be executed before its timelock expires")]
+    ConfigTimelockActive,
+
+    #[msg("Approvers must be unique, non-default and at most Multisig::MAX_APPROVERS")]
+    InvalidApprovers,
+
+    #[msg("Threshold must be between one and the number of approvers")]
+    InvalidThreshold,
+
+    #[msg("Signer is not an approver of the multisig")]
+    NotAnApprover,
+
+    #[msg("Approver already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Proposal does not have enough approvals")]
+    NotEnoughApprovals,
+
+    #[msg("Proposal was already executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Approvers changed since the proposal was created")]
+    StaleProposal,
+
+    #[msg("Proposed instruction exceeds the proposal size limits")]
+    ProposalTooLarge,
+
+    #[msg("Accounts do not match the proposed instruction")]
+    InvalidProposalAccounts,
+
+    #[msg("Trading on this curve is paused or frozen")]
+    CurveNotActive,
+
+    #[msg("Curve is frozen")]
+    CurveFrozen,
+
+    #[msg("Launches are paused")]
+    LaunchesPaused,
+
+    #[msg("Buys are paused")]
+    BuysPaused,
+
+    #[msg("Sells are paused")]
+    SellsPaused,
+
+    #[msg("Migrations are paused")]
+    MigrationsPaused,
+
+    #[msg("Curves only start unwinding through start_unwind")]
+    InvalidCurveStatus,
+
+    #[msg("Curve is unwinding")]
+    CurveUnwinding,
+
+    #[msg("Curve is not open for redemptions")]
+    UnwindNotReady,
+
+    #[msg("Existing pool trades too far from the curve's final price")]
+    PoolPriceMismatch,
+
+    #[msg("Max price impact must be greater than zero")]
+    InvalidPriceImpact,
+
+    #[msg("Account is not in the layout this upgrade expects")]
+    InvalidAccountLayout,
+
+    #[msg("AMM config is not the one set for this migration target")]
+    InvalidAmmConfig,
+
+    #[msg("Keeper reward does not cover the pool creation fee")]
+    KeeperRewardTooLow,
 }
@@ -0,0 +1,33 @@
+use crate::{errors::SwifeyError, states::Config, utils::AuthorityAccepted};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.pending_authority != Pubkey::default() @ SwifeyError::NoPendingAuthority,
+        constraint = global_config.pending_authority == new_authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> AcceptAuthority<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let old_authority = self.global_config.authority;
+
+        self.global_config.authority = self.new_authority.key();
+        self.global_config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityAccepted {
+            old_authority,
+            new_authority: self.new_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
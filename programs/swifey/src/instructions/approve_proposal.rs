@@ -0,0 +1,39 @@
+use crate::{errors::SwifeyError, states::{AdminProposal, Multisig}, utils::ProposalApproved};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    approver: Signer<'info>,
+
+    #[account(
+        seeds = [Multisig::SEED_PREFIX.as_bytes()],
+        bump = multisig.bump
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [AdminProposal::SEED_PREFIX.as_bytes(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = !proposal.is_executed @ SwifeyError::ProposalAlreadyExecuted,
+        constraint = proposal.multisig_version == multisig.version @ SwifeyError::StaleProposal
+    )]
+    proposal: Box<Account<'info, AdminProposal>>,
+}
+
+impl<'info> ApproveProposal<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let approver_index = self.multisig.approver_index(&self.approver.key())?;
+        self.proposal.approve(approver_index)?;
+
+        emit!(ProposalApproved {
+            proposal: self.proposal.key(),
+            approver: self.approver.key(),
+            approvals: self.proposal.approval_count(),
+            threshold: self.multisig.threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{errors::SwifeyError, states::Config, utils::AuthorityProposalCancelled};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAuthority<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress,
+        constraint = global_config.pending_authority != Pubkey::default() @ SwifeyError::NoPendingAuthority
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> CancelAuthority<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let pending_authority = self.global_config.pending_authority;
+        self.global_config.pending_authority = Pubkey::default();
+
+        emit!(AuthorityProposalCancelled {
+            authority: self.authority.key(),
+            pending_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{errors::SwifeyError, states::{Config, PendingConfig}, utils::ConfigChangeCancelled};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelConfig<'info> {
+    // The authority, or whoever queued the change
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key()
+            || pending_config.proposer == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [PendingConfig::SEED_PREFIX.as_bytes()],
+        bump = pending_config.bump,
+        close = proposer
+    )]
+    pending_config: Box<Account<'info, PendingConfig>>,
+
+    /// CHECK: Refunded the rent of the pending config
+    #[account(mut, address = pending_config.proposer @ SwifeyError::UnauthorizedAddress)]
+    proposer: AccountInfo<'info>,
+}
+
+impl<'info> CancelConfig<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        emit!(ConfigChangeCancelled {
+            authority: self.authority.key(),
+            proposer: self.pending_config.proposer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
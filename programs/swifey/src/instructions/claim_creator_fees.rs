@@ -0,0 +1,52 @@
+use crate::{errors::SwifeyError, states::BondingCurve, utils::{sol_transfer_with_signer, CreatorFeesClaimed}};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        constraint = bonding_curve.creator == creator.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimCreatorFees<'info> {
+    pub fn process(&mut self, bump: u8) -> Result<()> {
+        let amount = self.bonding_curve.creator_fees_accrued;
+        require!(amount > 0, SwifeyError::InsufficientFunds);
+
+        let token = self.token_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_signer(&token, &bump)];
+
+        // Reset the accrual before moving lamports out of the curve PDA
+        self.bonding_curve.creator_fees_accrued = 0;
+
+        sol_transfer_with_signer(
+            &self.bonding_curve.to_account_info(),
+            &self.creator.to_account_info(),
+            &self.system_program.to_account_info(),
+            signer_seeds,
+            amount,
+        )?;
+
+        emit!(CreatorFeesClaimed {
+            token_mint: token,
+            creator: self.creator.key(),
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{errors::SwifeyError, states::{Config, FeeVault}, utils::{sol_transfer_with_signer, FeesClaimed}};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [FeeVault::SEED_PREFIX.as_bytes()], bump = fee_vault.bump)]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// CHECK: This account is verified by through the global config constraint
+    #[account(mut, constraint = global_config.fee_recipient == fee_recipient.key() @ SwifeyError::IncorrectFeeRecipient)]
+    fee_recipient: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimFees<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        // Only fees recorded in the vault can leave it, never its rent reserve
+        let amount = self.fee_vault.unclaimed()?;
+        require!(amount > 0, SwifeyError::InsufficientFunds);
+
+        self.fee_vault.total_claimed = self.fee_vault.total_claimed
+            .checked_add(amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[FeeVault::SEED_PREFIX.as_bytes(), &[self.fee_vault.bump]]];
+        sol_transfer_with_signer(
+            &self.fee_vault.to_account_info(),
+            &self.fee_recipient,
+            &self.system_program.to_account_info(),
+            signer_seeds,
+            amount,
+        )?;
+
+        emit!(FeesClaimed {
+            authority: self.authority.key(),
+            fee_recipient: self.fee_recipient.key(),
+            amount,
+            total_accrued: self.fee_vault.total_accrued,
+            total_claimed: self.fee_vault.total_claimed,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
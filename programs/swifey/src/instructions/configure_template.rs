@@ -0,0 +1,58 @@
+use crate::{errors::SwifeyError, states::{Config, CurveParams, CurveTemplate}, utils::CurveTemplateUpdated};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct ConfigureTemplate<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [CurveTemplate::SEED_PREFIX.as_bytes(), &index.to_le_bytes()],
+        space = 8 + CurveTemplate::LEN,
+        bump
+    )]
+    curve_template: Box<Account<'info, CurveTemplate>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ConfigureTemplate<'info> {
+    pub fn process(&mut self, index: u16, params: CurveParams, is_active: bool, bump: u8) -> Result<()> {
+        // Templates go through the same validation as the global config
+        params.validate()?;
+
+        self.curve_template.index = index;
+        self.curve_template.params = params.clone();
+        self.curve_template.is_active = is_active;
+        self.curve_template.bump = bump;
+
+        emit!(CurveTemplateUpdated {
+            authority: self.authority.key(),
+            curve_template: self.curve_template.key(),
+            index,
+            curve_limit: params.curve_limit,
+            initial_virtual_token_reserve: params.initial_virtual_token_reserve,
+            initial_virtual_sol_reserve: params.initial_virtual_sol_reserve,
+            total_token_supply: params.total_token_supply,
+            buy_fee_percentage: params.buy_fee_percentage,
+            sell_fee_percentage: params.sell_fee_percentage,
+            migration_fee_percentage: params.migration_fee_percentage,
+            creator_fee_percentage: params.creator_fee_percentage,
+            is_active,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{constants::MULTISIG_SIGNER_SEED, errors::SwifeyError, states::{Config, Multisig}, utils::MultisigUpdated};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Multisig::SEED_PREFIX.as_bytes()],
+        space = 8 + Multisig::LEN,
+        bump
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+
+    /// CHECK: Signer PDA of the multisig, holds no data
+    #[account(seeds = [MULTISIG_SIGNER_SEED.as_bytes()], bump)]
+    multisig_signer: UncheckedAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateMultisig<'info> {
+    // Approval mode starts once the authority proposes the multisig signer as the new authority
+    // and a multisig proposal accepts it. Proposals that create accounts, like configure, are
+    // paid by the multisig signer, so it needs to be funded
+    pub fn process(&mut self, approvers: Vec<Pubkey>, threshold: u8, bump: u8, signer_bump: u8) -> Result<()> {
+        self.multisig.bump = bump;
+        self.multisig.signer_bump = signer_bump;
+        self.multisig.set_approvers(approvers, threshold)?;
+
+        emit!(MultisigUpdated {
+            multisig: self.multisig.key(),
+            approvers: self.multisig.approvers.clone(),
+            threshold,
+            version: self.multisig.version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
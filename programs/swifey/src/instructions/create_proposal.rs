@@ -0,0 +1,76 @@
+use crate::{errors::SwifeyError, states::{AdminProposal, Multisig, ProposalAccount}, utils::{ProposalApproved, ProposalCreated}};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Multisig::SEED_PREFIX.as_bytes()],
+        bump = multisig.bump
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [AdminProposal::SEED_PREFIX.as_bytes(), multisig.proposal_count.to_le_bytes().as_ref()],
+        space = 8 + AdminProposal::LEN,
+        bump
+    )]
+    proposal: Box<Account<'info, AdminProposal>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateProposal<'info> {
+    // Propose an instruction of this program, counted as approved by the proposer
+    pub fn process(&mut self, accounts: Vec<ProposalAccount>, data: Vec<u8>, bump: u8) -> Result<()> {
+        // 1. Only approvers can propose
+        let approver_index = self.multisig.approver_index(&self.proposer.key())?;
+
+        // 2. The instruction must fit the proposal account and carry a discriminator
+        require!(
+            accounts.len() <= AdminProposal::MAX_ACCOUNTS
+                && data.len() >= 8
+                && data.len() <= AdminProposal::MAX_DATA_LEN,
+            SwifeyError::ProposalTooLarge
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let index = self.multisig.proposal_count;
+        self.multisig.proposal_count = index
+            .checked_add(1)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        self.proposal.multisig_version = self.multisig.version;
+        self.proposal.index = index;
+        self.proposal.proposer = self.proposer.key();
+        self.proposal.accounts = accounts;
+        self.proposal.data = data;
+        self.proposal.approvals = 0;
+        self.proposal.is_executed = false;
+        self.proposal.created_at = now;
+        self.proposal.bump = bump;
+        self.proposal.approve(approver_index)?;
+
+        emit!(ProposalCreated {
+            proposal: self.proposal.key(),
+            index,
+            proposer: self.proposer.key(),
+            timestamp: now,
+        });
+        emit!(ProposalApproved {
+            proposal: self.proposal.key(),
+            approver: self.proposer.key(),
+            approvals: self.proposal.approval_count(),
+            threshold: self.multisig.threshold,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
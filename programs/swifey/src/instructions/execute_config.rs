@@ -0,0 +1,132 @@
+use crate::{
+    errors::SwifeyError,
+    states::{Config, ConfigChange, PendingConfig},
+    utils::{ConfigurationUpdated, CurveParamsUpdated, FeesUpdated},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteConfig<'info> {
+    // Anyone can apply a change once its timelock expired
+    executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        seeds = [PendingConfig::SEED_PREFIX.as_bytes()],
+        bump = pending_config.bump,
+        close = proposer
+    )]
+    pending_config: Box<Account<'info, PendingConfig>>,
+
+    /// CHECK: Refunded the rent of the pending config
+    #[account(mut, address = pending_config.proposer @ SwifeyError::UnauthorizedAddress)]
+    proposer: AccountInfo<'info>,
+}
+
+impl<'info> ExecuteConfig<'info> {
+    pub fn process(&mut self) -> Result<()> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            timestamp >= self.pending_config.execute_after,
+            SwifeyError::ConfigTimelockActive
+        );
+
+        // Merge the queued group into the config as it is now. The authority may have moved on
+        // since the change was queued
+        let old_config = self.global_config.settings();
+        let new_config = self.pending_config.change.apply(old_config.clone());
+        require!(
+            new_config.authority == self.global_config.authority,
+            SwifeyError::AuthorityChangeNotAllowed
+        );
+        new_config.validate()?;
+
+        self.global_config.update(&new_config);
+
+        // Only a configure call reports the full settings, set_fees and set_curve_params are
+        // reported by the targeted events below
+        if let ConfigChange::Full(_) = &self.pending_config.change {
+            emit!(ConfigurationUpdated {
+                admin: self.executor.key(),
+                old_authority: old_config.authority,
+                new_authority: self.global_config.authority,
+                old_fee_recipient: old_config.fee_recipient,
+                new_fee_recipient: new_config.fee_recipient,
+                old_curve_limit: old_config.curve_limit,
+                new_curve_limit: new_config.curve_limit,
+                old_buy_fee_percentage: old_config.buy_fee_percentage,
+                new_buy_fee_percentage: new_config.buy_fee_percentage,
+                old_sell_fee_percentage: old_config.sell_fee_percentage,
+                new_sell_fee_percentage: new_config.sell_fee_percentage,
+                old_migration_fee_percentage: old_config.migration_fee_percentage,
+                new_migration_fee_percentage: new_config.migration_fee_percentage,
+                old_creator_fee_percentage: old_config.creator_fee_percentage,
+                new_creator_fee_percentage: new_config.creator_fee_percentage,
+                old_keeper_reward_percentage: old_config.keeper_reward_percentage,
+                new_keeper_reward_percentage: new_config.keeper_reward_percentage,
+                old_lp_policy: old_config.lp_policy,
+                new_lp_policy: new_config.lp_policy,
+                old_migration_target: old_config.migration_target,
+                new_migration_target: new_config.migration_target,
+                old_clmm_amm_config: old_config.clmm_amm_config,
+                new_clmm_amm_config: new_config.clmm_amm_config,
+                old_cpmm_amm_config: old_config.cpmm_amm_config,
+                new_cpmm_amm_config: new_config.cpmm_amm_config,
+                old_pause_flags: old_config.pause_flags,
+                new_pause_flags: self.global_config.pause_flags,
+                timestamp,
+            });
+        }
+
+        // Targeted events for the groups that actually changed
+        let fees_changed = old_config.buy_fee_percentage != new_config.buy_fee_percentage
+            || old_config.sell_fee_percentage != new_config.sell_fee_percentage
+            || old_config.migration_fee_percentage != new_config.migration_fee_percentage
+            || old_config.creator_fee_percentage != new_config.creator_fee_percentage
+            || old_config.keeper_reward_percentage != new_config.keeper_reward_percentage;
+        if fees_changed {
+            emit!(FeesUpdated {
+                authority: self.pending_config.proposer,
+                old_buy_fee_percentage: old_config.buy_fee_percentage,
+                new_buy_fee_percentage: new_config.buy_fee_percentage,
+                old_sell_fee_percentage: old_config.sell_fee_percentage,
+                new_sell_fee_percentage: new_config.sell_fee_percentage,
+                old_migration_fee_percentage: old_config.migration_fee_percentage,
+                new_migration_fee_percentage: new_config.migration_fee_percentage,
+                old_creator_fee_percentage: old_config.creator_fee_percentage,
+                new_creator_fee_percentage: new_config.creator_fee_percentage,
+                old_keeper_reward_percentage: old_config.keeper_reward_percentage,
+                new_keeper_reward_percentage: new_config.keeper_reward_percentage,
+                timestamp,
+            });
+        }
+
+        let curve_params_changed = old_config.curve_limit != new_config.curve_limit
+            || old_config.initial_virtual_token_reserve != new_config.initial_virtual_token_reserve
+            || old_config.initial_virtual_sol_reserve != new_config.initial_virtual_sol_reserve
+            || old_config.total_token_supply != new_config.total_token_supply;
+        if curve_params_changed {
+            emit!(CurveParamsUpdated {
+                authority: self.pending_config.proposer,
+                old_curve_limit: old_config.curve_limit,
+                new_curve_limit: new_config.curve_limit,
+                old_initial_virtual_token_reserve: old_config.initial_virtual_token_reserve,
+                new_initial_virtual_token_reserve: new_config.initial_virtual_token_reserve,
+                old_initial_virtual_sol_reserve: old_config.initial_virtual_sol_reserve,
+                new_initial_virtual_sol_reserve: new_config.initial_virtual_sol_reserve,
+                old_total_token_supply: old_config.total_token_supply,
+                new_total_token_supply: new_config.total_token_supply,
+                timestamp,
+            });
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    constants::MULTISIG_SIGNER_SEED,
+    errors::SwifeyError,
+    program::Swifey,
+    states::{AdminProposal, Multisig},
+    utils::ProposalExecuted,
+};
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    // Anyone can execute a proposal once it has enough approvals
+    executor: Signer<'info>,
+
+    // Not mutable here: a proposal may update the multisig itself through set_multisig
+    #[account(
+        seeds = [Multisig::SEED_PREFIX.as_bytes()],
+        bump = multisig.bump
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+
+    #[account(
+        mut,
+        seeds = [AdminProposal::SEED_PREFIX.as_bytes(), proposal.index.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = !proposal.is_executed @ SwifeyError::ProposalAlreadyExecuted,
+        constraint = proposal.multisig_version == multisig.version @ SwifeyError::StaleProposal
+    )]
+    proposal: Box<Account<'info, AdminProposal>>,
+
+    /// CHECK: Signer PDA of the multisig, signs the proposed instruction
+    #[account(seeds = [MULTISIG_SIGNER_SEED.as_bytes()], bump = multisig.signer_bump)]
+    multisig_signer: UncheckedAccount<'info>,
+
+    program: Program<'info, Swifey>,
+}
+
+// The accounts of the proposed instruction follow as remaining accounts, in proposal order
+pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+    let multisig = &ctx.accounts.multisig;
+    let proposal = &mut ctx.accounts.proposal;
+
+    // 1. Check the approvals
+    require!(
+        proposal.approval_count() >= multisig.threshold,
+        SwifeyError::NotEnoughApprovals
+    );
+
+    // 2. Check the passed accounts match the proposed ones
+    require!(
+        ctx.remaining_accounts.len() == proposal.accounts.len()
+            && ctx.remaining_accounts
+                .iter()
+                .zip(proposal.accounts.iter())
+                .all(|(info, account)| info.key() == account.pubkey),
+        SwifeyError::InvalidProposalAccounts
+    );
+
+    // Persist the executed flag before the call, so the proposal cannot run again from inside it
+    proposal.is_executed = true;
+    proposal.exit(&crate::ID)?;
+
+    let instruction = proposal.instruction(&ctx.accounts.multisig_signer.key());
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.program.to_account_info());
+
+    let signer_bump = multisig.signer_bump;
+    let seeds = Multisig::get_signer(&signer_bump);
+    invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+
+    emit!(ProposalExecuted {
+        proposal: proposal.key(),
+        executor: ctx.accounts.executor.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
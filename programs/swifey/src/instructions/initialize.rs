@@ -0,0 +1,77 @@
+use crate::{errors::SwifeyError, program::Swifey, states::{Config, ConfigSettings, FeeVault}, utils::ConfigurationInitialized};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    // Only the program's upgrade authority can create the config, so nobody can front-run it
+    // between deployment and initialization
+    #[account(mut)]
+    admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        space = 8 + Config::LEN,
+        bump
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        space = 8 + FeeVault::LEN,
+        bump
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ SwifeyError::UnauthorizedAddress)]
+    program: Program<'info, Swifey>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SwifeyError::UnauthorizedAddress)]
+    program_data: Box<Account<'info, ProgramData>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn process(&mut self, new_config: ConfigSettings, fee_vault_bump: u8) -> Result<()> {
+        // Validate configuration parameters
+        new_config.validate()?;
+
+        self.fee_vault.bump = fee_vault_bump;
+        self.global_config.authority = new_config.authority;
+        self.global_config.pause_flags = new_config.pause_flags;
+        self.global_config.update(&new_config);
+
+        emit_config_initialized(self.admin.key(), &new_config)
+    }
+}
+
+// Shared by initialize and upgrade_config, which both start a config from full settings
+pub fn emit_config_initialized(admin: Pubkey, new_config: &ConfigSettings) -> Result<()> {
+    emit!(ConfigurationInitialized {
+        admin,
+        authority: new_config.authority,
+        fee_recipient: new_config.fee_recipient,
+        curve_limit: new_config.curve_limit,
+        initial_virtual_token_reserve: new_config.initial_virtual_token_reserve,
+        initial_virtual_sol_reserve: new_config.initial_virtual_sol_reserve,
+        total_token_supply: new_config.total_token_supply,
+        buy_fee_percentage: new_config.buy_fee_percentage,
+        sell_fee_percentage: new_config.sell_fee_percentage,
+        migration_fee_percentage: new_config.migration_fee_percentage,
+        creator_fee_percentage: new_config.creator_fee_percentage,
+        keeper_reward_percentage: new_config.keeper_reward_percentage,
+        lp_policy: new_config.lp_policy,
+        migration_target: new_config.migration_target,
+        clmm_amm_config: new_config.clmm_amm_config,
+        cpmm_amm_config: new_config.cpmm_amm_config,
+        pause_flags: new_config.pause_flags,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
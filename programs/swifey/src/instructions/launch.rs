@@ -1,5 +1,5 @@
 use crate::{
-    constants::TOKEN_DECIMAL, errors::SwifeyError, states::{BondingCurve, Config}, constants::{LAMPORTS_PER_SOL},  utils::{TokenLaunched}
+    constants::TOKEN_DECIMAL, errors::SwifeyError, states::{BondingCurve, Config, CurveStatus, CurveTemplate},  utils::{TokenLaunched}
 };
 
 use anchor_lang::{prelude::*, system_program, solana_program::sysvar};
@@ -15,9 +15,10 @@ pub fn launch(
     name: String,
     symbol: String,
     uri: String,
+    template_index: Option<u16>,
 ) -> Result<()> {
-    // Check if contract is paused
-    require!(!ctx.accounts.global_config.is_paused, SwifeyError::ContractPaused);
+    // Check if launches are paused
+    require!(!ctx.accounts.global_config.pause_flags.launch, SwifeyError::LaunchesPaused);
 
     let rent = Rent::get()?;
     let min_rent = rent.minimum_balance(ctx.accounts.bonding_curve.to_account_info().data_len());
@@ -43,46 +44,41 @@ pub fn launch(
     let bonding_curve = &mut ctx.accounts.bonding_curve;
     let global_config = &ctx.accounts.global_config;
 
+    // Use the selected curve template, or the global config as the default tier
+    let params = match (template_index, &ctx.accounts.curve_template) {
+        // The seeds constraint ties the template account to template_index
+        (Some(_), Some(curve_template)) => curve_template.params.clone(),
+        (None, None) => global_config.curve_params(),
+        _ => return err!(SwifeyError::InvalidCurveTemplate),
+    };
+
     // Validate initial parameters
-    // 1. Validate virtual token reserve is at least 80% of total supply
-    require!(
-        global_config.initial_virtual_token_reserve >= (global_config.total_token_supply * 80) / 100,
-        SwifeyError::InvalidTokenAllocation
-    );
-
-    // 2. Validate initial SOL reserve is non-zero and reasonable
-    require!(
-        global_config.initial_virtual_sol_reserve >= LAMPORTS_PER_SOL, // At least 1 SOL
-        SwifeyError::InsufficientLiquidity
-    );
-
-    // 3. Validate curve limit is greater than initial SOL reserve
-    require!(
-        global_config.curve_limit > global_config.initial_virtual_sol_reserve,
-        SwifeyError::InvalidCurveLimit
-    );
-
-    // 4. Validate total token supply is non-zero
-    require!(
-        global_config.total_token_supply > 0,
-        SwifeyError::InvalidTokenAllocation
-    );
-
-    // 5. Validate initial real token reserve is zero or less than virtual reserve
-    require!(
-        global_config.initial_real_token_reserve <= global_config.initial_virtual_token_reserve,
-        SwifeyError::InvalidTokenAllocation
-    );
+    params.validate()?;
 
     // init bonding curve pda with initial SOL balance including rent buffer
-    bonding_curve.virtual_token_reserve = global_config.initial_virtual_token_reserve;
-    bonding_curve.virtual_sol_reserve = global_config.initial_virtual_sol_reserve;
-    bonding_curve.real_token_reserve = global_config.initial_real_token_reserve;
-    bonding_curve.real_sol_reserve = rent_buffer; // Initialize with rent buffer
-    bonding_curve.token_total_supply = global_config.total_token_supply;
+    bonding_curve.virtual_token_reserve = params.initial_virtual_token_reserve;
+    bonding_curve.virtual_sol_reserve = params.initial_virtual_sol_reserve;
+    bonding_curve.real_token_reserve = params.total_token_supply; // The full supply is minted to the curve below
+    bonding_curve.real_sol_reserve = 0; // The rent buffer is not curve liquidity
+    bonding_curve.token_total_supply = params.total_token_supply;
     bonding_curve.is_completed = false;
     bonding_curve.is_migrated = false;
 
+    // Freeze the curve parameters so later config changes only affect new launches
+    bonding_curve.initial_virtual_sol_reserve = params.initial_virtual_sol_reserve;
+    bonding_curve.curve_limit = params.curve_limit;
+    bonding_curve.buy_fee_percentage = params.buy_fee_percentage;
+    bonding_curve.sell_fee_percentage = params.sell_fee_percentage;
+    bonding_curve.migration_fee_percentage = params.migration_fee_percentage;
+    bonding_curve.creator_fee_percentage = params.creator_fee_percentage;
+    bonding_curve.migration_target = global_config.migration_target;
+    bonding_curve.status = CurveStatus::Active;
+    bonding_curve.unwind_at = 0;
+
+    // Record the creator so creator fees can be claimed later
+    bonding_curve.creator = ctx.accounts.creator.key();
+    bonding_curve.creator_fees_accrued = 0;
+
     let signer_seeds: &[&[&[u8]]] = &[&[Config::SEED_PREFIX.as_bytes(), &[ctx.bumps.global_config]]];
 
     //  mint token to bonding curve
@@ -96,7 +92,7 @@ pub fn launch(
             },
             signer_seeds,
         ),
-        global_config.total_token_supply,
+        params.total_token_supply,
     )?;
 
     //  create metadata
@@ -148,10 +144,16 @@ pub fn launch(
         symbol: symbol,
         uri: uri,
         creator: ctx.accounts.creator.key(),
-        initial_virtual_sol_reserve: global_config.initial_virtual_sol_reserve,
-        initial_virtual_token_reserve: global_config.initial_virtual_token_reserve,
-        total_token_supply: global_config.total_token_supply,
-        curve_limit: global_config.curve_limit
+        initial_virtual_sol_reserve: params.initial_virtual_sol_reserve,
+        initial_virtual_token_reserve: params.initial_virtual_token_reserve,
+        total_token_supply: params.total_token_supply,
+        curve_limit: params.curve_limit,
+        buy_fee_percentage: params.buy_fee_percentage,
+        sell_fee_percentage: params.sell_fee_percentage,
+        migration_fee_percentage: params.migration_fee_percentage,
+        creator_fee_percentage: params.creator_fee_percentage,
+        template_index,
+        migration_target: global_config.migration_target,
     });
 
     Ok(())
@@ -159,6 +161,7 @@ pub fn launch(
 
 #[derive(Accounts)]
 #[event_cpi]
+#[instruction(name: String, symbol: String, uri: String, template_index: Option<u16>)]
 pub struct Launch<'info>{
     #[account(mut)]
     creator: Signer<'info>,
@@ -169,6 +172,14 @@ pub struct Launch<'info>{
     )]
     global_config: Box<Account<'info, Config>>,
 
+    // Optional launch tier, the global config is used when omitted
+    #[account(
+        seeds = [CurveTemplate::SEED_PREFIX.as_bytes(), &template_index.unwrap_or_default().to_le_bytes()],
+        bump = curve_template.bump,
+        constraint = curve_template.is_active @ SwifeyError::CurveTemplateInactive
+    )]
+    curve_template: Option<Box<Account<'info, CurveTemplate>>>,
+
     #[account(
         init, 
         payer = creator,
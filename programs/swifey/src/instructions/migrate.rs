@@ -1,26 +1,42 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
-use crate::states::{BondingCurve, Config};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, AssociatedToken},
+    token::{self, Token, TokenAccount, Mint},
+};
+use crate::constants::LP_LOCK_SEED;
+use crate::states::{BondingCurve, Config, CurveStatus, FeeVault, LpPolicy, MigrationAmounts, MigrationTarget};
 use crate::errors::SwifeyError;
-use crate::utils::{sol_transfer_with_signer, token_transfer_with_signer, MigrationCompleted};
-use raydium_amm_v3::{
-    self,
-    states::{AmmConfig, POOL_SEED, POOL_TICK_ARRAY_BITMAP_SEED, POOL_VAULT_SEED},
-    program::AmmV3,
-    libraries::tick_math,
+use crate::utils::{
+    sol_transfer_with_signer, token_burn_with_signer, token_close_with_signer, token_transfer_with_signer,
+    MigrationCompleted
 };
 
+// Migrated SOL and tokens on the pool side. Raydium orders the two pool mints by key, so WSOL
+// is token_0 only when its mint sorts before the project token
+pub struct PoolDeposit<'info> {
+    pub mint_0: AccountInfo<'info>,
+    pub mint_1: AccountInfo<'info>,
+    pub account_0: AccountInfo<'info>,
+    pub account_1: AccountInfo<'info>,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+// Accounts used by every migration target, nested in MigrateClmm and MigrateCpmm
 #[derive(Accounts)]
-pub struct Migrate<'info> {
+pub struct MigrateCommon<'info> {
+    // Anyone can migrate a completed curve and collect the keeper reward, unless the
+    // authority assigned a migrator
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub keeper: Signer<'info>,
 
     #[account(
         mut,
         seeds = [Config::SEED_PREFIX.as_bytes()],
         bump,
+        constraint = config.can_migrate(&keeper.key()) @ SwifeyError::UnauthorizedAddress,
     )]
-    pub config: Account<'info, Config>,
+    pub config: Box<Account<'info, Config>>,
 
     #[account(
         mut,
@@ -29,203 +45,299 @@ pub struct Migrate<'info> {
         constraint = bonding_curve.is_completed @ SwifeyError::CurveNotCompleted,
         constraint = !bonding_curve.is_migrated @ SwifeyError::AlreadyMigrated,
     )]
-    pub bonding_curve: Account<'info, BondingCurve>,
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
 
     #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: Box<Account<'info, Mint>>,
 
-    /// CHECK: WSOL mint
-    pub wsol_mint: Account<'info, Mint>,
+    #[account(address = token::spl_token::native_mint::ID @ SwifeyError::InvalidPoolTokens)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
 
     #[account(
         mut,
         constraint = curve_token_account.owner == bonding_curve.key(),
         constraint = curve_token_account.amount > 0 @ SwifeyError::InsufficientTokenBalance,
     )]
-    pub curve_token_account: Account<'info, TokenAccount>,
+    pub curve_token_account: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Native SOL account owned by bonding curve
+    // WSOL account of the bonding curve, the migrated SOL is wrapped into it
     #[account(
-        mut,
-        constraint = *curve_sol_account.owner == bonding_curve.key(),
-        constraint = curve_sol_account.lamports() > 0 @ SwifeyError::InsufficientSolBalance,
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = wsol_mint,
+        associated_token::authority = bonding_curve,
     )]
-    pub curve_sol_account: AccountInfo<'info>,
+    pub curve_wsol_account: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Pool state account to be created
-    #[account(
-        mut,
-        seeds = [
-            POOL_SEED.as_bytes(),
-            amm_config.key().as_ref(),
-            wsol_mint.key().as_ref(),
-            token_mint.key().as_ref(),
-        ],
-        seeds::program = raydium_program,
-        bump,
-    )]
-    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: LP lock PDA, owns locked liquidity and never signs
+    #[account(seeds = [LP_LOCK_SEED.as_bytes()], bump)]
+    pub lp_lock: UncheckedAccount<'info>,
 
-    /// CHECK: Pool observation state account
+    /// CHECK: Liquidity account of the LP lock, created and checked when the policy is Lock
     #[account(mut)]
-    pub observation_state: UncheckedAccount<'info>,
-
-    /// CHECK: Token vault for WSOL
-    #[account(
-        mut,
-        seeds = [
-            POOL_VAULT_SEED.as_bytes(),
-            pool_state.key().as_ref(),
-            wsol_mint.key().as_ref(),
-        ],
-        seeds::program = raydium_program,
-        bump,
-    )]
-    pub token_vault_0: UncheckedAccount<'info>,
+    pub lp_lock_account: UncheckedAccount<'info>,
 
-    /// CHECK: Token vault for project token
-    #[account(
-        mut,
-        seeds = [
-            POOL_VAULT_SEED.as_bytes(),
-            pool_state.key().as_ref(),
-            token_mint.key().as_ref(),
-        ],
-        seeds::program = raydium_program,
-        bump,
-    )]
-    pub token_vault_1: UncheckedAccount<'info>,
-
-    /// CHECK: Initialize an account to store if a tick array is initialized
-    #[account(
-        mut,
-        seeds = [
-            POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
-            pool_state.key().as_ref(),
-        ],
-        seeds::program = raydium_program,
-        bump,
-    )]
-    pub tick_array_bitmap: UncheckedAccount<'info>,
-
-    /// CHECK: Fee recipient account
-    #[account(mut)]
-    pub fee_recipient: AccountInfo<'info>,
-
-    /// CHECK: Raydium program state account
-    pub amm_config: Box<Account<'info, AmmConfig>>,
+    #[account(mut, seeds = [FeeVault::SEED_PREFIX.as_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub raydium_program: Program<'info, AmmV3>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-impl<'info> Migrate<'info> {
-    pub fn process(ctx: Context<Migrate>) -> Result<()> {
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        let config = &ctx.accounts.config;
-        
-        // Verify authority
+impl<'info> MigrateCommon<'info> {
+    // Pay the migration fee and keeper reward, then wrap the SOL to migrate into the curve's
+    // WSOL account
+    pub fn prepare(&mut self, target: MigrationTarget, signer_seeds: &[&[&[u8]]]) -> Result<MigrationAmounts> {
+        // 1. The curve migrates to the pool type frozen at launch
         require!(
-            config.authority == ctx.accounts.authority.key(),
-            SwifeyError::UnauthorizedAddress
+            self.bonding_curve.migration_target == target,
+            SwifeyError::InvalidMigrationTarget
         );
 
-        // Calculate amounts and fees
-        let sol_balance = ctx.accounts.curve_sol_account.lamports();
-        let token_balance = ctx.accounts.curve_token_account.amount;
-        
-        // Calculate migration fee
-        let migration_fee = sol_balance
-            .checked_mul(config.migration_fee_percentage as u64)
-            .ok_or(SwifeyError::MathOverflow)?
-            .checked_div(100)
-            .ok_or(SwifeyError::MathOverflow)?;
-        
-        let remaining_sol = sol_balance
-            .checked_sub(migration_fee)
-            .ok_or(SwifeyError::InsufficientSolBalance)?;
-
-        // Create Raydium pool
-        let init_sqrt_price = tick_math::get_sqrt_price_at_tick(0)?; // Start at tick 0 for 1:1 price
-        let open_time = Clock::get()?.unix_timestamp as u64;
-        
-        let create_pool_accounts = raydium_amm_v3::cpi::accounts::CreatePool {
-            pool_creator: ctx.accounts.authority.to_account_info(),
-            amm_config: ctx.accounts.amm_config.to_account_info(),
-            pool_state: ctx.accounts.pool_state.to_account_info(),
-            token_mint_0: ctx.accounts.wsol_mint.to_account_info(),
-            token_mint_1: ctx.accounts.token_mint.to_account_info(),
-            token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
-            token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
-            observation_state: ctx.accounts.observation_state.to_account_info(),
-            tick_array_bitmap: ctx.accounts.tick_array_bitmap.to_account_info(),
-            token_program_0: ctx.accounts.token_program.to_account_info(),
-            token_program_1: ctx.accounts.token_program.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
-        };
-
-        let create_pool_ctx = CpiContext::new(
-            ctx.accounts.raydium_program.to_account_info(),
-            create_pool_accounts,
+        // 2. Migrations can be paused globally, and a frozen curve keeps its funds until the
+        // authority lifts the freeze
+        require!(!self.config.pause_flags.migrate, SwifeyError::MigrationsPaused);
+        require!(
+            self.bonding_curve.status != CurveStatus::Frozen,
+            SwifeyError::CurveFrozen
+        );
+        require!(
+            self.bonding_curve.status != CurveStatus::Unwinding,
+            SwifeyError::CurveUnwinding
         );
 
-        raydium_amm_v3::cpi::create_pool(
-            create_pool_ctx,
-            init_sqrt_price,
-            open_time,
-        )?;
+        let amounts = self.bonding_curve.migration_amounts(self.config.keeper_reward_percentage)?;
 
-        // Get signer seeds for PDA operations
-        let bump = ctx.bumps.bonding_curve;
-        let token_key = ctx.accounts.token_mint.key();
-        let seeds = BondingCurve::get_signer(
-            &token_key,
-            &bump
-        );
-        let signer_seeds = &[&seeds[..]];
-    
-        // Transfer migration fee to fee recipient
+        // Transfer the platform fee to the fee vault and the keeper reward to the keeper
         sol_transfer_with_signer(
-            &ctx.accounts.curve_sol_account.to_account_info(),
-            &ctx.accounts.fee_recipient,
-            &ctx.accounts.system_program,
+            &self.bonding_curve.to_account_info(),
+            &self.fee_vault.to_account_info(),
+            &self.system_program,
             signer_seeds,
-            migration_fee,
+            amounts.platform_fee,
         )?;
-    
-        // Transfer remaining SOL to Raydium pool
+        self.fee_vault.accrue(self.token_mint.key(), amounts.platform_fee)?;
+
         sol_transfer_with_signer(
-            &ctx.accounts.curve_sol_account.to_account_info(),
-            &ctx.accounts.token_vault_0.to_account_info(), // Transfer to WSOL vault
-            &ctx.accounts.system_program,
+            &self.bonding_curve.to_account_info(),
+            &self.keeper.to_account_info(),
+            &self.system_program,
             signer_seeds,
-            remaining_sol,
+            amounts.keeper_reward,
         )?;
-    
-        // Transfer tokens to Raydium pool
-        token_transfer_with_signer(
-            &ctx.accounts.curve_token_account.to_account_info(),
-            &bonding_curve.to_account_info(),
-            &ctx.accounts.token_vault_1.to_account_info(), // Transfer to token vault
-            &ctx.accounts.token_program.to_account_info(),
+
+        // Wrap the remaining SOL: move the lamports into the curve's WSOL account, then sync its
+        // token balance so the deposit goes through the token program
+        sol_transfer_with_signer(
+            &self.bonding_curve.to_account_info(),
+            &self.curve_wsol_account.to_account_info(),
+            &self.system_program,
             signer_seeds,
-            token_balance,
+            amounts.sol_amount,
         )?;
-    
+        token::sync_native(CpiContext::new(
+            self.token_program.to_account_info(),
+            token::SyncNative {
+                account: self.curve_wsol_account.to_account_info(),
+            },
+        ))?;
+
+        Ok(amounts)
+    }
+
+    // WSOL and token balances of the curve accounts, read again since CPIs moved them
+    pub fn curve_balances(&mut self) -> Result<(u64, u64)> {
+        self.curve_wsol_account.reload()?;
+        self.curve_token_account.reload()?;
+        Ok((self.curve_wsol_account.amount, self.curve_token_account.amount))
+    }
+
+    // A pool that was not created by the migration can take less than the migrated amount on
+    // one side, and nothing could withdraw the rest once the curve's reserves are zeroed.
+    // Leftover SOL is unwrapped into the fee vault and leftover tokens are burned. Returns the
+    // amounts that went into the pool
+    pub fn sweep_leftovers(
+        &mut self,
+        amounts: MigrationAmounts,
+        balances_before: (u64, u64),
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<MigrationAmounts> {
+        // What the pool took of the migrated amounts, read from the curve balances
+        let (wsol_balance, token_balance) = self.curve_balances()?;
+        let sol_deposited = balances_before.0
+            .checked_sub(wsol_balance)
+            .ok_or(SwifeyError::MathOverflow)?;
+        let tokens_deposited = balances_before.1
+            .checked_sub(token_balance)
+            .ok_or(SwifeyError::MathOverflow)?;
+        let sol_left = amounts.sol_amount
+            .checked_sub(sol_deposited)
+            .ok_or(SwifeyError::MathOverflow)?;
+        let tokens_left = amounts.token_amount
+            .checked_sub(tokens_deposited)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        let curve_info = self.bonding_curve.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        if sol_left > 0 {
+            // Closing the WSOL account unwraps all of its lamports, rent included, into the vault
+            let fee_vault_info = self.fee_vault.to_account_info();
+            let vault_lamports = fee_vault_info.lamports();
+            token_close_with_signer(
+                &self.curve_wsol_account.to_account_info(),
+                &fee_vault_info,
+                &curve_info,
+                &token_program,
+                signer_seeds,
+            )?;
+            let received = fee_vault_info.lamports()
+                .checked_sub(vault_lamports)
+                .ok_or(SwifeyError::MathOverflow)?;
+            self.fee_vault.accrue(self.token_mint.key(), received)?;
+        }
+
+        if tokens_left > 0 {
+            token_burn_with_signer(
+                &self.token_mint.to_account_info(),
+                &self.curve_token_account.to_account_info(),
+                &curve_info,
+                &token_program,
+                signer_seeds,
+                tokens_left,
+            )?;
+        }
+
+        Ok(MigrationAmounts {
+            sol_amount: sol_deposited,
+            token_amount: tokens_deposited,
+            ..amounts
+        })
+    }
+
+    pub fn wsol_is_token_0(&self) -> bool {
+        self.wsol_mint.key() < self.token_mint.key()
+    }
+
+    pub fn pool_mint_0(&self) -> Pubkey {
+        if self.wsol_is_token_0() { self.wsol_mint.key() } else { self.token_mint.key() }
+    }
+
+    pub fn pool_mint_1(&self) -> Pubkey {
+        if self.wsol_is_token_0() { self.token_mint.key() } else { self.wsol_mint.key() }
+    }
+
+    // Put the migrated amounts and the accounts holding them in pool order
+    pub fn pool_deposit(
+        &self,
+        amounts: &MigrationAmounts,
+        wsol_account: AccountInfo<'info>,
+        token_account: AccountInfo<'info>,
+    ) -> PoolDeposit<'info> {
+        let wsol = (self.wsol_mint.to_account_info(), wsol_account, amounts.sol_amount);
+        let token = (self.token_mint.to_account_info(), token_account, amounts.token_amount);
+        let (side_0, side_1) = if self.wsol_is_token_0() { (wsol, token) } else { (token, wsol) };
+
+        PoolDeposit {
+            mint_0: side_0.0,
+            mint_1: side_1.0,
+            account_0: side_0.1,
+            account_1: side_1.1,
+            amount_0: side_0.2,
+            amount_1: side_1.2,
+        }
+    }
+
+    // Burn the liquidity received from the pool, or move it to the LP lock, so it can never be
+    // withdrawn by anyone
+    pub fn apply_lp_policy(
+        &self,
+        lp_mint: &AccountInfo<'info>,
+        lp_account: &AccountInfo<'info>,
+        lp_authority: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        amount: u64,
+    ) -> Result<LpPolicy> {
+        let lp_policy = self.config.lp_policy;
+        let token_program = self.token_program.to_account_info();
+
+        match lp_policy {
+            LpPolicy::Burn => {
+                token_burn_with_signer(
+                    lp_mint,
+                    lp_account,
+                    lp_authority,
+                    &token_program,
+                    signer_seeds,
+                    amount,
+                )?;
+            }
+            LpPolicy::Lock => {
+                require!(
+                    self.lp_lock_account.key() == get_associated_token_address(&self.lp_lock.key(), &lp_mint.key()),
+                    SwifeyError::InvalidLpLockAccount
+                );
+
+                associated_token::create(CpiContext::new(
+                    self.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: self.keeper.to_account_info(),
+                        associated_token: self.lp_lock_account.to_account_info(),
+                        authority: self.lp_lock.to_account_info(),
+                        mint: lp_mint.clone(),
+                        system_program: self.system_program.to_account_info(),
+                        token_program: token_program.clone(),
+                    },
+                ))?;
+
+                token_transfer_with_signer(
+                    lp_account,
+                    lp_authority,
+                    &self.lp_lock_account.to_account_info(),
+                    &token_program,
+                    signer_seeds,
+                    amount,
+                )?;
+            }
+        }
+
+        Ok(lp_policy)
+    }
+
+    // Mark the curve migrated once its reserves are in the pool, and report the migration
+    pub fn finish(
+        &mut self,
+        amounts: &MigrationAmounts,
+        raydium_pool: Pubkey,
+        lp_mint: Pubkey,
+        liquidity: u128,
+        lp_policy: LpPolicy,
+    ) -> Result<()> {
         // Update migration state atomically
-        bonding_curve.update_migration_state()?;
-    
-        emit!(MigrationCompleted{
-            token_mint: ctx.accounts.token_mint.key(),
-            sol_amount: remaining_sol,
-            token_amount: token_balance,
-            migration_fee: migration_fee,
-            raydium_pool: ctx.accounts.pool_state.key(),
+        self.bonding_curve.complete_migration()?;
+
+        // Everything left on the curve must still be accounted for
+        self.bonding_curve.check_solvency(
+            &self.bonding_curve.to_account_info(),
+            &self.curve_token_account.to_account_info(),
+        )?;
+
+        emit!(MigrationCompleted {
+            token_mint: self.token_mint.key(),
+            sol_amount: amounts.sol_amount,
+            token_amount: amounts.token_amount,
+            migration_fee: amounts.migration_fee,
+            keeper: self.keeper.key(),
+            keeper_reward: amounts.keeper_reward,
+            migration_target: self.bonding_curve.migration_target,
+            raydium_pool,
+            lp_mint,
+            liquidity,
+            lp_policy,
         });
-        
+
         Ok(())
     }
-}
\ No newline at end of file
+}
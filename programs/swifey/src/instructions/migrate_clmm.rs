@@ -0,0 +1,315 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::metadata::Metadata;
+use crate::constants::{FEE_PRECISION, MAX_POOL_PRICE_DEVIATION_BPS};
+use crate::instructions::migrate::*;
+use crate::states::{BondingCurve, MigrationTarget};
+use crate::errors::SwifeyError;
+use crate::utils::{
+    sqrt_price_x64, token_approve_with_signer, token_close_with_signer, token_revoke_with_signer
+};
+use raydium_amm_v3::{
+    self,
+    states::{AmmConfig, PoolState, TickArrayState, POOL_SEED, POOL_TICK_ARRAY_BITMAP_SEED, POOL_VAULT_SEED},
+    program::AmmV3,
+    libraries::{liquidity_math, tick_math},
+};
+
+#[derive(Accounts)]
+pub struct MigrateClmm<'info> {
+    pub common: MigrateCommon<'info>,
+
+    /// CHECK: Pool state account, created here unless someone created it before the migration
+    #[account(
+        mut,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            common.pool_mint_0().as_ref(),
+            common.pool_mint_1().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: Pool observation state account
+    #[account(mut)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for token_0
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            common.pool_mint_0().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for token_1
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            common.pool_mint_1().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_1: UncheckedAccount<'info>,
+
+    /// CHECK: Initialize an account to store if a tick array is initialized
+    #[account(
+        mut,
+        seeds = [
+            POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub tick_array_bitmap: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array holding the lower tick of the full-range position, created by Raydium
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array holding the upper tick of the full-range position, created by Raydium
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    // Fresh mint for the position NFT, held by the bonding curve
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: Position NFT token account of the bonding curve, created by Raydium
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// CHECK: Position NFT metadata account, created by Raydium
+    #[account(mut)]
+    pub metadata_account: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium protocol position for the full tick range, validated by Raydium
+    #[account(mut)]
+    pub protocol_position: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium personal position for the position NFT, created by Raydium
+    #[account(mut)]
+    pub personal_position: UncheckedAccount<'info>,
+
+    // Fee tier of the pool, fixed by the authority so a keeper cannot pick another one
+    #[account(
+        address = common.config.amm_config(MigrationTarget::RaydiumClmm) @ SwifeyError::InvalidAmmConfig,
+    )]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub raydium_program: Program<'info, AmmV3>,
+}
+
+impl<'info> MigrateClmm<'info> {
+    pub fn process(ctx: Context<MigrateClmm>) -> Result<()> {
+        // Get signer seeds for PDA operations
+        let bump = ctx.bumps.common.bonding_curve;
+        let token_key = ctx.accounts.common.token_mint.key();
+        let seeds = BondingCurve::get_signer(
+            &token_key,
+            &bump
+        );
+        let signer_seeds = &[&seeds[..]];
+
+        let amounts = ctx.accounts.common.prepare(MigrationTarget::RaydiumClmm, signer_seeds)?;
+        let balances_before = ctx.accounts.common.curve_balances()?;
+        let common = &ctx.accounts.common;
+        let deposit = common.pool_deposit(
+            &amounts,
+            common.curve_wsol_account.to_account_info(),
+            common.curve_token_account.to_account_info(),
+        );
+
+        // Open the pool at the curve's final price: token_1 per token_0 in base units, which
+        // already carries the 9 vs TOKEN_DECIMAL decimals of the two mints
+        let init_sqrt_price = sqrt_price_x64(deposit.amount_0, deposit.amount_1)?;
+        require!(
+            init_sqrt_price > tick_math::MIN_SQRT_PRICE_X64 && init_sqrt_price < tick_math::MAX_SQRT_PRICE_X64,
+            SwifeyError::InvalidPoolState
+        );
+
+        // The pool address only depends on the mints and the AMM config, so anyone can create
+        // it before the migration. Reuse such a pool when it trades close to the curve's final
+        // price, so squatting the address cannot block the migration. An empty pool at another
+        // price can be moved to the right price with a swap before migrating
+        let pool_info = ctx.accounts.pool_state.to_account_info();
+        let pool_sqrt_price = if pool_info.data_is_empty() {
+            let create_pool_accounts = raydium_amm_v3::cpi::accounts::CreatePool {
+                pool_creator: common.keeper.to_account_info(),
+                amm_config: ctx.accounts.amm_config.to_account_info(),
+                pool_state: pool_info.clone(),
+                token_mint_0: deposit.mint_0.clone(),
+                token_mint_1: deposit.mint_1.clone(),
+                token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
+                token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
+                observation_state: ctx.accounts.observation_state.to_account_info(),
+                tick_array_bitmap: ctx.accounts.tick_array_bitmap.to_account_info(),
+                token_program_0: common.token_program.to_account_info(),
+                token_program_1: common.token_program.to_account_info(),
+                system_program: common.system_program.to_account_info(),
+                rent: common.rent.to_account_info(),
+            };
+
+            let create_pool_ctx = CpiContext::new(
+                ctx.accounts.raydium_program.to_account_info(),
+                create_pool_accounts,
+            );
+
+            raydium_amm_v3::cpi::create_pool(
+                create_pool_ctx,
+                init_sqrt_price,
+                Clock::get()?.unix_timestamp as u64,
+            )?;
+            init_sqrt_price
+        } else {
+            let pool_sqrt_price = read_pool_sqrt_price(&pool_info)?;
+            let deviation = pool_sqrt_price.abs_diff(init_sqrt_price)
+                .checked_mul(FEE_PRECISION as u128)
+                .ok_or(SwifeyError::MathOverflow)?;
+            let max_deviation = init_sqrt_price
+                .checked_mul(MAX_POOL_PRICE_DEVIATION_BPS as u128)
+                .ok_or(SwifeyError::MathOverflow)?;
+            require!(deviation <= max_deviation, SwifeyError::PoolPriceMismatch);
+            pool_sqrt_price
+        };
+
+        // Provide the liquidity over the full tick range, so the pool trades at any price
+        let tick_spacing = ctx.accounts.amm_config.tick_spacing;
+        let tick_lower_index = tick_math::MIN_TICK / tick_spacing as i32 * tick_spacing as i32;
+        let tick_upper_index = tick_math::MAX_TICK / tick_spacing as i32 * tick_spacing as i32;
+        // At the price of a reused pool, part of one side can stay on the curve accounts until
+        // it is swept below
+        let liquidity = liquidity_math::get_liquidity_from_amounts(
+            pool_sqrt_price,
+            tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
+            tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+            deposit.amount_0,
+            deposit.amount_1,
+        );
+        require!(liquidity > 0, SwifeyError::InsufficientLiquidity);
+
+        // Raydium pulls the deposit with the payer's authority, so let the keeper spend exactly
+        // the migrated amounts from the curve accounts for the duration of the CPI
+        let curve_info = common.bonding_curve.to_account_info();
+        let token_program = common.token_program.to_account_info();
+        token_approve_with_signer(
+            &common.curve_wsol_account.to_account_info(),
+            &common.keeper.to_account_info(),
+            &curve_info,
+            &token_program,
+            signer_seeds,
+            amounts.sol_amount,
+        )?;
+        token_approve_with_signer(
+            &common.curve_token_account.to_account_info(),
+            &common.keeper.to_account_info(),
+            &curve_info,
+            &token_program,
+            signer_seeds,
+            amounts.token_amount,
+        )?;
+
+        let open_position_accounts = raydium_amm_v3::cpi::accounts::OpenPosition {
+            payer: common.keeper.to_account_info(),
+            position_nft_owner: curve_info.clone(),
+            position_nft_mint: ctx.accounts.position_nft_mint.to_account_info(),
+            position_nft_account: ctx.accounts.position_nft_account.to_account_info(),
+            metadata_account: ctx.accounts.metadata_account.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            protocol_position: ctx.accounts.protocol_position.to_account_info(),
+            tick_array_lower: ctx.accounts.tick_array_lower.to_account_info(),
+            tick_array_upper: ctx.accounts.tick_array_upper.to_account_info(),
+            personal_position: ctx.accounts.personal_position.to_account_info(),
+            token_account_0: deposit.account_0.clone(),
+            token_account_1: deposit.account_1.clone(),
+            token_vault_0: ctx.accounts.token_vault_0.to_account_info(),
+            token_vault_1: ctx.accounts.token_vault_1.to_account_info(),
+            rent: common.rent.to_account_info(),
+            system_program: common.system_program.to_account_info(),
+            token_program: token_program.clone(),
+            associated_token_program: common.associated_token_program.to_account_info(),
+            metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        };
+
+        // Full-range ticks fall outside the default bitmap, so Raydium needs the extension
+        let open_position_ctx = CpiContext::new(
+            ctx.accounts.raydium_program.to_account_info(),
+            open_position_accounts,
+        )
+        .with_remaining_accounts(vec![ctx.accounts.tick_array_bitmap.to_account_info()]);
+
+        raydium_amm_v3::cpi::open_position(
+            open_position_ctx,
+            tick_lower_index,
+            tick_upper_index,
+            TickArrayState::get_array_start_index(tick_lower_index, tick_spacing),
+            TickArrayState::get_array_start_index(tick_upper_index, tick_spacing),
+            liquidity,
+            deposit.amount_0,
+            deposit.amount_1,
+        )?;
+
+        token_revoke_with_signer(
+            &common.curve_wsol_account.to_account_info(),
+            &curve_info,
+            &token_program,
+            signer_seeds,
+        )?;
+        token_revoke_with_signer(
+            &common.curve_token_account.to_account_info(),
+            &curve_info,
+            &token_program,
+            signer_seeds,
+        )?;
+
+        // Apply the LP policy to the position NFT held by the curve
+        let position_nft_account = ctx.accounts.position_nft_account.to_account_info();
+        let lp_policy = common.apply_lp_policy(
+            &ctx.accounts.position_nft_mint.to_account_info(),
+            &position_nft_account,
+            &curve_info,
+            signer_seeds,
+            1,
+        )?;
+
+        // The curve's position NFT account is empty now, return its rent to the keeper who paid it
+        token_close_with_signer(
+            &position_nft_account,
+            &common.keeper.to_account_info(),
+            &curve_info,
+            &token_program,
+            signer_seeds,
+        )?;
+
+        let amounts = ctx.accounts.common.sweep_leftovers(amounts, balances_before, signer_seeds)?;
+
+        let raydium_pool = ctx.accounts.pool_state.key();
+        let position_nft_mint = ctx.accounts.position_nft_mint.key();
+        ctx.accounts.common.finish(&amounts, raydium_pool, position_nft_mint, liquidity, lp_policy)
+    }
+}
+
+// Current sqrt price of a Raydium CLMM pool that was created outside of the migration
+fn read_pool_sqrt_price(pool_info: &AccountInfo) -> Result<u128> {
+    require_keys_eq!(*pool_info.owner, raydium_amm_v3::ID, SwifeyError::InvalidPoolState);
+
+    let data = pool_info.try_borrow_data()?;
+    let pool_state = data
+        .get(..8 + std::mem::size_of::<PoolState>())
+        .filter(|data| data[..8] == PoolState::discriminator())
+        .map(|data| bytemuck::pod_read_unaligned::<PoolState>(&data[8..]))
+        .ok_or(SwifeyError::InvalidPoolState)?;
+    Ok(pool_state.sqrt_price_x64)
+}
@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    token::TokenAccount,
+};
+use crate::instructions::migrate::*;
+use crate::states::{BondingCurve, MigrationTarget};
+use crate::errors::SwifeyError;
+use crate::utils::{
+    cpmm_initialize, read_create_pool_fee, token_transfer_with_signer, CpmmInitialize, CPMM_AUTH_SEED,
+    CPMM_OBSERVATION_SEED, CPMM_POOL_LP_MINT_SEED, CPMM_POOL_VAULT_SEED, raydium_cpmm_program,
+};
+
+#[derive(Accounts)]
+pub struct MigrateCpmm<'info> {
+    pub common: MigrateCommon<'info>,
+
+    /// CHECK: Raydium CPMM vault and LP mint authority
+    #[account(
+        seeds = [CPMM_AUTH_SEED.as_bytes()],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    // Fresh keypair for the pool state, which Raydium CPMM accepts in place of the PDA of the
+    // pair. Anyone can create the PDA pool before the migration, a keeper-chosen address cannot
+    // be taken in advance
+    #[account(mut)]
+    pub pool_state: Signer<'info>,
+
+    /// CHECK: LP token mint of the pool, created by Raydium
+    #[account(
+        mut,
+        seeds = [
+            CPMM_POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    // Raydium CPMM takes the deposit from token accounts owned by the pool creator, so the
+    // migrated amounts pass through the keeper's accounts within this instruction
+    /// CHECK: WSOL account of the keeper, created if needed
+    #[account(
+        mut,
+        address = get_associated_token_address(&common.keeper.key(), &common.wsol_mint.key()),
+    )]
+    pub keeper_wsol_account: UncheckedAccount<'info>,
+
+    /// CHECK: Project token account of the keeper, created if needed
+    #[account(
+        mut,
+        address = get_associated_token_address(&common.keeper.key(), &common.token_mint.key()),
+    )]
+    pub keeper_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: LP token account of the keeper, created by Raydium
+    #[account(mut)]
+    pub keeper_lp_account: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for token_0
+    #[account(
+        mut,
+        seeds = [
+            CPMM_POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            common.pool_mint_0().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault for token_1
+    #[account(
+        mut,
+        seeds = [
+            CPMM_POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            common.pool_mint_1().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub token_vault_1: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium pool creation fee receiver, validated by Raydium
+    #[account(mut)]
+    pub create_pool_fee: UncheckedAccount<'info>,
+
+    /// CHECK: Pool observation state account
+    #[account(
+        mut,
+        seeds = [
+            CPMM_OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        seeds::program = raydium_program,
+        bump,
+    )]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium CPMM config account set by the authority, read by read_create_pool_fee
+    #[account(
+        address = common.config.amm_config(MigrationTarget::RaydiumCpmm) @ SwifeyError::InvalidAmmConfig,
+        owner = raydium_cpmm_program::ID,
+    )]
+    pub amm_config: UncheckedAccount<'info>,
+
+    /// CHECK: Raydium CPMM program
+    #[account(address = raydium_cpmm_program::ID)]
+    pub raydium_program: UncheckedAccount<'info>,
+}
+
+impl<'info> MigrateCpmm<'info> {
+    pub fn process(ctx: Context<MigrateCpmm>) -> Result<()> {
+        // Get signer seeds for PDA operations
+        let bump = ctx.bumps.common.bonding_curve;
+        let token_key = ctx.accounts.common.token_mint.key();
+        let seeds = BondingCurve::get_signer(
+            &token_key,
+            &bump
+        );
+        let signer_seeds = &[&seeds[..]];
+
+        let amounts = ctx.accounts.common.prepare(MigrationTarget::RaydiumCpmm, signer_seeds)?;
+
+        // The keeper pays Raydium's pool creation fee, the reward has to cover it or no keeper
+        // would migrate
+        require!(
+            amounts.keeper_reward >= read_create_pool_fee(&ctx.accounts.amm_config.to_account_info())?,
+            SwifeyError::KeeperRewardTooLow
+        );
+        let common = &ctx.accounts.common;
+
+        // Hand the migrated amounts to the keeper's accounts for the pool deposit
+        let curve_info = common.bonding_curve.to_account_info();
+        let token_program = common.token_program.to_account_info();
+        for (keeper_account, mint) in [
+            (&ctx.accounts.keeper_wsol_account, common.wsol_mint.to_account_info()),
+            (&ctx.accounts.keeper_token_account, common.token_mint.to_account_info()),
+        ] {
+            associated_token::create_idempotent(CpiContext::new(
+                common.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: common.keeper.to_account_info(),
+                    associated_token: keeper_account.to_account_info(),
+                    authority: common.keeper.to_account_info(),
+                    mint,
+                    system_program: common.system_program.to_account_info(),
+                    token_program: token_program.clone(),
+                },
+            ))?;
+        }
+
+        token_transfer_with_signer(
+            &common.curve_wsol_account.to_account_info(),
+            &curve_info,
+            &ctx.accounts.keeper_wsol_account.to_account_info(),
+            &token_program,
+            signer_seeds,
+            amounts.sol_amount,
+        )?;
+        token_transfer_with_signer(
+            &common.curve_token_account.to_account_info(),
+            &curve_info,
+            &ctx.accounts.keeper_token_account.to_account_info(),
+            &token_program,
+            signer_seeds,
+            amounts.token_amount,
+        )?;
+
+        // Create the pool, the deposit ratio sets its price to the curve's final price
+        let deposit = common.pool_deposit(
+            &amounts,
+            ctx.accounts.keeper_wsol_account.to_account_info(),
+            ctx.accounts.keeper_token_account.to_account_info(),
+        );
+        let open_time = Clock::get()?.unix_timestamp as u64;
+
+        let initialize_accounts = CpmmInitialize {
+            creator: common.keeper.to_account_info(),
+            amm_config: ctx.accounts.amm_config.to_account_info(),
+            authority: ctx.accounts.pool_authority.to_account_info(),
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            token_0_mint: deposit.mint_0,
+            token_1_mint: deposit.mint_1,
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            creator_token_0: deposit.account_0,
+            creator_token_1: deposit.account_1,
+            creator_lp_token: ctx.accounts.keeper_lp_account.to_account_info(),
+            token_0_vault: ctx.accounts.token_vault_0.to_account_info(),
+            token_1_vault: ctx.accounts.token_vault_1.to_account_info(),
+            create_pool_fee: ctx.accounts.create_pool_fee.to_account_info(),
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            token_program: token_program.clone(),
+            token_0_program: token_program.clone(),
+            token_1_program: token_program.clone(),
+            associated_token_program: common.associated_token_program.to_account_info(),
+            system_program: common.system_program.to_account_info(),
+            rent: common.rent.to_account_info(),
+        };
+
+        cpmm_initialize(
+            &ctx.accounts.raydium_program.to_account_info(),
+            initialize_accounts,
+            deposit.amount_0,
+            deposit.amount_1,
+            open_time,
+        )?;
+
+        // All LP tokens minted to the keeper come from this deposit, since the pool is new
+        let lp_amount = {
+            let data = ctx.accounts.keeper_lp_account.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut &data[..])?.amount
+        };
+        require!(lp_amount > 0, SwifeyError::InsufficientLiquidity);
+
+        // The keeper signed this instruction, so its authority carries over to the LP tokens
+        let lp_policy = common.apply_lp_policy(
+            &ctx.accounts.lp_mint.to_account_info(),
+            &ctx.accounts.keeper_lp_account.to_account_info(),
+            &common.keeper.to_account_info(),
+            &[],
+            lp_amount,
+        )?;
+
+        let raydium_pool = ctx.accounts.pool_state.key();
+        let lp_mint = ctx.accounts.lp_mint.key();
+        ctx.accounts.common.finish(&amounts, raydium_pool, lp_mint, lp_amount as u128, lp_policy)
+    }
+}
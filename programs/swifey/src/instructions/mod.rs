@@ -1,6 +1,69 @@
+pub mod initialize;
+pub use initialize::*;
+
+pub mod upgrade_config;
+pub use upgrade_config::*;
+
 pub mod configure;
 pub use configure::*;
 
+pub mod execute_config;
+pub use execute_config::*;
+
+pub mod cancel_config;
+pub use cancel_config::*;
+
+pub mod set_pause;
+pub use set_pause::*;
+
+pub mod set_fees;
+pub use set_fees::*;
+
+pub mod set_fee_recipient;
+pub use set_fee_recipient::*;
+
+pub mod set_curve_params;
+pub use set_curve_params::*;
+
+pub mod set_curve_status;
+pub use set_curve_status::*;
+
+pub mod start_unwind;
+pub use start_unwind::*;
+
+pub mod redeem;
+pub use redeem::*;
+
+pub mod set_role;
+pub use set_role::*;
+
+pub mod propose_authority;
+pub use propose_authority::*;
+
+pub mod accept_authority;
+pub use accept_authority::*;
+
+pub mod cancel_authority;
+pub use cancel_authority::*;
+
+pub mod create_multisig;
+pub use create_multisig::*;
+
+pub mod set_multisig;
+pub use set_multisig::*;
+
+pub mod create_proposal;
+pub use create_proposal::*;
+
+pub mod approve_proposal;
+pub use approve_proposal::*;
+
+pub mod execute_proposal;
+pub use execute_proposal::*;
+
+pub mod configure_template;
+pub use configure_template::*;
+
 pub mod launch;
 pub use launch::*;
 
@@ -8,4 +71,19 @@ pub mod swap;
 pub use swap::*;
 
 pub mod migrate;
-pub use migrate::*;
\ No newline at end of file
+pub use migrate::*;
+
+pub mod migrate_clmm;
+pub use migrate_clmm::*;
+
+pub mod migrate_cpmm;
+pub use migrate_cpmm::*;
+
+pub mod claim_creator_fees;
+pub use claim_creator_fees::*;
+
+pub mod claim_fees;
+pub use claim_fees::*;
+
+pub mod upgrade_curve;
+pub use upgrade_curve::*;
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::{errors::SwifeyError, states::Config, utils::AuthorityProposed};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> ProposeAuthority<'info> {
+    pub fn process(&mut self, new_authority: Pubkey) -> Result<()> {
+        // The proposed key has to sign accept_authority, so a typo cannot lock out the program
+        require!(new_authority != Pubkey::default(), SwifeyError::UnauthorizedAddress);
+
+        // A new proposal replaces any pending one
+        self.global_config.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            authority: self.authority.key(),
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    errors::SwifeyError,
+    states::{BondingCurve, CurveStatus},
+    utils::{sol_transfer_with_signer, token_transfer_user, TokensRedeemed},
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.status == CurveStatus::Unwinding @ SwifeyError::UnwindNotReady
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = token_mint, associated_token::authority = bonding_curve)]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_mint, token::authority = user)]
+    user_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = token::ID)]
+    token_program: Program<'info, Token>,
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Redeem<'info> {
+    // Return tokens of an unwinding curve for their pro-rata share of its real SOL reserve
+    pub fn process(&mut self, amount: u64, bump: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.user_token_account.amount >= amount,
+            SwifeyError::InsufficientTokenBalance
+        );
+
+        let sol_amount = self.bonding_curve.redeem(amount, now)?;
+
+        token_transfer_user(
+            &self.user_token_account.to_account_info(),
+            &self.curve_token_account.to_account_info(),
+            &self.user.to_account_info(),
+            &self.token_program.to_account_info(),
+            amount,
+        )?;
+
+        let token = self.token_mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_signer(&token, &bump)];
+        sol_transfer_with_signer(
+            &self.bonding_curve.to_account_info(),
+            &self.user.to_account_info(),
+            &self.system_program.to_account_info(),
+            signer_seeds,
+            sol_amount,
+        )?;
+
+        self.curve_token_account.reload()?;
+        self.bonding_curve.check_solvency(
+            &self.bonding_curve.to_account_info(),
+            &self.curve_token_account.to_account_info(),
+        )?;
+
+        emit!(TokensRedeemed {
+            token_mint: token,
+            holder: self.user.key(),
+            token_amount: amount,
+            sol_amount,
+            real_sol_reserve: self.bonding_curve.real_sol_reserve,
+            real_token_reserve: self.bonding_curve.real_token_reserve,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{errors::SwifeyError, states::{Config, ConfigChange, CurveSettings, PendingConfig}};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct SetCurveParams<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PendingConfig::SEED_PREFIX.as_bytes()],
+        space = 8 + PendingConfig::LEN,
+        bump
+    )]
+    pending_config: Box<Account<'info, PendingConfig>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> SetCurveParams<'info> {
+    // Queue new curve parameters only, validated together with the fees of the config.
+    // Only new launches use them, live curves keep the parameters frozen at their launch
+    pub fn process(&mut self, curve: CurveSettings, bump: u8) -> Result<()> {
+        self.pending_config.queue(&self.global_config, ConfigChange::Curve(curve), self.authority.key(), bump)
+    }
+}
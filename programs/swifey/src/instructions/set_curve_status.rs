@@ -0,0 +1,54 @@
+use crate::{errors::SwifeyError, states::{BondingCurve, Config, CurveStatus}, utils::CurveStatusUpdated};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct SetCurveStatus<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = !bonding_curve.is_migrated @ SwifeyError::AlreadyMigrated
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl<'info> SetCurveStatus<'info> {
+    // Halt or resume a single curve, independently of the global pause
+    pub fn process(&mut self, status: CurveStatus) -> Result<()> {
+        let old_status = self.bonding_curve.status;
+        let now = Clock::get()?.unix_timestamp;
+
+        // 1. Unwinding has its own instruction and timelock
+        require!(status != CurveStatus::Unwinding, SwifeyError::InvalidCurveStatus);
+
+        // 2. An unwind can be called off until redemptions open, then it is final
+        if old_status == CurveStatus::Unwinding {
+            require!(now < self.bonding_curve.unwind_at, SwifeyError::CurveUnwinding);
+            self.bonding_curve.unwind_at = 0;
+        }
+
+        self.bonding_curve.status = status;
+
+        emit!(CurveStatusUpdated {
+            token_mint: self.token_mint.key(),
+            authority: self.authority.key(),
+            old_status,
+            new_status: status,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
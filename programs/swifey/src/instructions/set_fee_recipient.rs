@@ -0,0 +1,34 @@
+use crate::{errors::SwifeyError, states::Config, utils::FeeRecipientUpdated};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> SetFeeRecipient<'info> {
+    pub fn process(&mut self, fee_recipient: Pubkey) -> Result<()> {
+        // Fees claimed to the default pubkey would be lost
+        require!(fee_recipient != Pubkey::default(), SwifeyError::IncorrectFeeRecipient);
+
+        let old_fee_recipient = self.global_config.fee_recipient;
+        self.global_config.fee_recipient = fee_recipient;
+
+        emit!(FeeRecipientUpdated {
+            authority: self.authority.key(),
+            old_fee_recipient,
+            new_fee_recipient: fee_recipient,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
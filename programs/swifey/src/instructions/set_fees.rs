@@ -0,0 +1,34 @@
+use crate::{errors::SwifeyError, states::{Config, ConfigChange, FeeSettings, PendingConfig}};
+use anchor_lang::{prelude::*, system_program};
+
+#[derive(Accounts)]
+pub struct SetFees<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.can_set_fees(&authority.key()) @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [PendingConfig::SEED_PREFIX.as_bytes()],
+        space = 8 + PendingConfig::LEN,
+        bump
+    )]
+    pending_config: Box<Account<'info, PendingConfig>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> SetFees<'info> {
+    // Queue new fees only, validated together with the curve parameters of the config
+    pub fn process(&mut self, fees: FeeSettings, bump: u8) -> Result<()> {
+        self.pending_config.queue(&self.global_config, ConfigChange::Fees(fees), self.authority.key(), bump)
+    }
+}
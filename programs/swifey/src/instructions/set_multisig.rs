@@ -0,0 +1,32 @@
+use crate::{constants::MULTISIG_SIGNER_SEED, states::Multisig, utils::MultisigUpdated};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetMultisig<'info> {
+    // Only reachable through an approved proposal, signed by execute_proposal
+    #[account(seeds = [MULTISIG_SIGNER_SEED.as_bytes()], bump = multisig.signer_bump)]
+    multisig_signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Multisig::SEED_PREFIX.as_bytes()],
+        bump = multisig.bump
+    )]
+    multisig: Box<Account<'info, Multisig>>,
+}
+
+impl<'info> SetMultisig<'info> {
+    pub fn process(&mut self, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        self.multisig.set_approvers(approvers, threshold)?;
+
+        emit!(MultisigUpdated {
+            multisig: self.multisig.key(),
+            approvers: self.multisig.approvers.clone(),
+            threshold,
+            version: self.multisig.version,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{errors::SwifeyError, states::{Config, PauseFlags}, utils::PauseUpdated};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.can_pause(&authority.key()) @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> SetPause<'info> {
+    // Replaces all flags at once, e.g. pause buys only so holders can still sell
+    pub fn process(&mut self, pause_flags: PauseFlags) -> Result<()> {
+        let old_pause_flags = self.global_config.pause_flags;
+        self.global_config.pause_flags = pause_flags;
+
+        emit!(PauseUpdated {
+            authority: self.authority.key(),
+            old_pause_flags,
+            new_pause_flags: pause_flags,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
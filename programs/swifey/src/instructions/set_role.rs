@@ -0,0 +1,33 @@
+use crate::{errors::SwifeyError, states::{Config, Role}, utils::RoleUpdated};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+}
+
+impl<'info> SetRole<'info> {
+    // Pubkey::default() revokes the role
+    pub fn process(&mut self, role: Role, account: Pubkey) -> Result<()> {
+        let old_account = self.global_config.role(role);
+        self.global_config.set_role(role, account);
+
+        emit!(RoleUpdated {
+            authority: self.authority.key(),
+            role,
+            old_account,
+            new_account: account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
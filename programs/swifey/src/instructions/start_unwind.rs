@@ -0,0 +1,64 @@
+use crate::{
+    constants::MIN_UNWIND_DELAY,
+    errors::SwifeyError,
+    states::{BondingCurve, Config, CurveStatus},
+    utils::{CurveStatusUpdated, CurveUnwindStarted},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct StartUnwind<'info> {
+    authority: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        constraint = global_config.authority == authority.key() @ SwifeyError::UnauthorizedAddress
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), token_mint.key().as_ref()],
+        bump,
+        constraint = !bonding_curve.is_migrated @ SwifeyError::AlreadyMigrated,
+        constraint = bonding_curve.status != CurveStatus::Unwinding @ SwifeyError::CurveUnwinding
+    )]
+    bonding_curve: Box<Account<'info, BondingCurve>>,
+}
+
+impl<'info> StartUnwind<'info> {
+    // Halt a stuck curve for good and let its holders redeem once the config timelock expires,
+    // and never sooner than MIN_UNWIND_DELAY.
+    // Until then set_curve_status can still call it off
+    pub fn process(&mut self) -> Result<()> {
+        let old_status = self.bonding_curve.status;
+        let now = Clock::get()?.unix_timestamp;
+        let unwind_at = now
+            .checked_add(self.global_config.config_timelock.max(MIN_UNWIND_DELAY))
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        self.bonding_curve.status = CurveStatus::Unwinding;
+        self.bonding_curve.unwind_at = unwind_at;
+
+        emit!(CurveStatusUpdated {
+            token_mint: self.token_mint.key(),
+            authority: self.authority.key(),
+            old_status,
+            new_status: CurveStatus::Unwinding,
+            timestamp: now,
+        });
+        emit!(CurveUnwindStarted {
+            token_mint: self.token_mint.key(),
+            authority: self.authority.key(),
+            unwind_at,
+            real_sol_reserve: self.bonding_curve.real_sol_reserve,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     errors::SwifeyError,
-    states::{BondingCurve, Config}, utils::{CurveCompleted, TokenPurchased, TokenSold},
+    states::{BondingCurve, Config, CurveStatus, FeeVault, SwapSettlement}, utils::{CurveCompleted, TokenPurchased, TokenSold},
 };
 
 use anchor_lang::{prelude::*, system_program};
@@ -14,13 +14,15 @@ pub fn swap(ctx: Context<Swap>, amount: u64, direction: u8, min_out: u64) -> Res
     let bonding_curve = &mut ctx.accounts.bonding_curve;
     let global_config = &ctx.accounts.global_config;
     
-    // Check if contract is paused
-    require!(!global_config.is_paused, SwifeyError::ContractPaused);
+    require!(bonding_curve.status == CurveStatus::Active, SwifeyError::CurveNotActive);
     
     require!(bonding_curve.is_completed == false, SwifeyError::CurveLimitReached);
     
     require!(direction == 0 || direction == 1, SwifeyError::InvalidDirection);
 
+    // Buys and sells are paused separately
+    global_config.pause_flags.check_swap(direction)?;
+
     // Add minimum amount check (0.001 SOL = 1_000_000 lamports)
     if direction == 0 {
         require!(amount >= 1_000_000, SwifeyError::DustAmount);
@@ -37,77 +39,150 @@ pub fn swap(ctx: Context<Swap>, amount: u64, direction: u8, min_out: u64) -> Res
 
     let curve_pda = &mut bonding_curve.to_account_info();
 
-    if direction == 0 {
-        let (amount_in, amount_out, fee_amount, new_sol_reserves, new_token_reserves, is_completed) = bonding_curve.buy(
+    let settlement = if direction == 0 {
+        bonding_curve.buy(
+            &ctx.accounts.token_mint,
+            &ctx.accounts.user,
+            curve_pda,
+            &mut ctx.accounts.fee_vault,
+            &mut ctx.accounts.user_token_account.to_account_info(),
+            &mut ctx.accounts.curve_token_account.to_account_info(),
+            amount,
+            min_out,
+            ctx.bumps.bonding_curve,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?
+    } else {
+        bonding_curve.sell(
             &ctx.accounts.token_mint,
-            global_config,
             &ctx.accounts.user,
             curve_pda,
-            &mut ctx.accounts.fee_recipient,
             &mut ctx.accounts.user_token_account.to_account_info(),
+            &mut ctx.accounts.fee_vault,
             &mut ctx.accounts.curve_token_account.to_account_info(),
             amount,
             min_out,
             ctx.bumps.bonding_curve,
             &ctx.accounts.system_program.to_account_info(),
             &ctx.accounts.token_program.to_account_info(),
-        )?;
+        )?
+    };
 
-        // Calculate price as token/sol ratio with higher precision
-        let price = (new_token_reserves as u128)
-            .checked_mul(1_000_000)
-            .and_then(|v| v.checked_div(new_sol_reserves as u128))
-            .ok_or(SwifeyError::MathOverflow)?;
+    // Reject trades that move the price more than the configured limit
+    require!(
+        settlement.price_impact_bps <= ctx.accounts.global_config.max_price_impact,
+        SwifeyError::ExcessivePriceImpact
+    );
 
-        if is_completed {
-            emit_cpi!(CurveCompleted {
-                token_mint: ctx.accounts.token_mint.key(),
-                final_sol_reserve: new_sol_reserves,
-                final_token_reserve: new_token_reserves,
-            });
-        }
+    emit_swap_events(&ctx, direction, &settlement)
+}
 
-        emit_cpi!(TokenPurchased {
-            token_mint: ctx.accounts.token_mint.key(),
-            buyer: ctx.accounts.user.key(),
-            sol_amount: amount_in,
-            token_amount: amount_out,
-            fee_amount: fee_amount,
-            price: price as u64,
-            new_sol_reserves: new_sol_reserves,
-            new_token_reserves: new_token_reserves,
-        });
-    } else if direction == 1 {
-        let (amount_in, amount_out, fee_amount, new_sol_reserves, new_token_reserves) = bonding_curve.sell(
+pub fn swap_exact_out(ctx: Context<Swap>, amount_out: u64, direction: u8, max_in: u64) -> Result<()> {
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let global_config = &ctx.accounts.global_config;
+
+    require!(bonding_curve.status == CurveStatus::Active, SwifeyError::CurveNotActive);
+
+    require!(bonding_curve.is_completed == false, SwifeyError::CurveLimitReached);
+
+    require!(direction == 0 || direction == 1, SwifeyError::InvalidDirection);
+
+    // Buys and sells are paused separately
+    global_config.pause_flags.check_swap(direction)?;
+
+    // Same dust minimums as swap, the curve checks them against the input it prices for
+    // amount_out: at least 0.001 SOL for buys and 1000 token units for sells
+    require!(amount_out > 0, SwifeyError::DustAmount);
+
+    let curve_pda = &mut bonding_curve.to_account_info();
+
+    let settlement = if direction == 0 {
+        bonding_curve.buy_exact_out(
             &ctx.accounts.token_mint,
-            global_config,
             &ctx.accounts.user,
             curve_pda,
+            &mut ctx.accounts.fee_vault,
             &mut ctx.accounts.user_token_account.to_account_info(),
-            &mut ctx.accounts.fee_recipient,
             &mut ctx.accounts.curve_token_account.to_account_info(),
-            amount,
-            min_out,
+            amount_out,
+            max_in,
+            ctx.bumps.bonding_curve,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?
+    } else {
+        bonding_curve.sell_exact_out(
+            &ctx.accounts.token_mint,
+            &ctx.accounts.user,
+            curve_pda,
+            &mut ctx.accounts.user_token_account.to_account_info(),
+            &mut ctx.accounts.fee_vault,
+            &mut ctx.accounts.curve_token_account.to_account_info(),
+            amount_out,
+            max_in,
             ctx.bumps.bonding_curve,
             &ctx.accounts.system_program.to_account_info(),
             &ctx.accounts.token_program.to_account_info(),
-        )?;
+        )?
+    };
 
-        // Calculate price as token/sol ratio with higher precision
-        let price = (new_token_reserves as u128)
-            .checked_mul(1_000_000)
-            .and_then(|v| v.checked_div(new_sol_reserves as u128))
-            .ok_or(SwifeyError::MathOverflow)?;
+    // Reject trades that move the price more than the configured limit
+    require!(
+        settlement.price_impact_bps <= ctx.accounts.global_config.max_price_impact,
+        SwifeyError::ExcessivePriceImpact
+    );
 
+    emit_swap_events(&ctx, direction, &settlement)
+}
+
+fn emit_swap_events(ctx: &Context<Swap>, direction: u8, settlement: &SwapSettlement) -> Result<()> {
+    // Calculate price as token/sol ratio with higher precision
+    let price = (settlement.new_token_reserves as u128)
+        .checked_mul(1_000_000)
+        .and_then(|v| v.checked_div(settlement.new_sol_reserves as u128))
+        .ok_or(SwifeyError::MathOverflow)?;
+
+    if direction == 0 {
+        if settlement.is_completed {
+            emit_cpi!(CurveCompleted {
+                token_mint: ctx.accounts.token_mint.key(),
+                final_sol_reserve: settlement.new_sol_reserves,
+                final_token_reserve: settlement.new_token_reserves,
+            });
+        }
+
+        emit_cpi!(TokenPurchased {
+            token_mint: ctx.accounts.token_mint.key(),
+            buyer: ctx.accounts.user.key(),
+            sol_amount: settlement.amount_in,
+            token_amount: settlement.amount_out,
+            fee_amount: settlement.fee_amount,
+            creator: ctx.accounts.bonding_curve.creator,
+            creator_fee_amount: settlement.creator_fee_amount,
+            price: price as u64,
+            new_sol_reserves: settlement.new_sol_reserves,
+            new_token_reserves: settlement.new_token_reserves,
+            real_sol_reserve: settlement.real_sol_reserve,
+            real_token_reserve: settlement.real_token_reserve,
+            price_impact_bps: settlement.price_impact_bps,
+            refund_amount: settlement.refund_amount,
+        });
+    } else {
         emit_cpi!(TokenSold {
             token_mint: ctx.accounts.token_mint.key(),
             buyer: ctx.accounts.user.key(),
-            sol_amount: amount_out,
-            token_amount: amount_in,
-            fee_amount: fee_amount,
+            sol_amount: settlement.amount_out,
+            token_amount: settlement.amount_in,
+            fee_amount: settlement.fee_amount,
+            creator: ctx.accounts.bonding_curve.creator,
+            creator_fee_amount: settlement.creator_fee_amount,
             price: price as u64,
-            new_sol_reserves: new_sol_reserves,
-            new_token_reserves: new_token_reserves,
+            new_sol_reserves: settlement.new_sol_reserves,
+            new_token_reserves: settlement.new_token_reserves,
+            real_sol_reserve: settlement.real_sol_reserve,
+            real_token_reserve: settlement.real_token_reserve,
+            price_impact_bps: settlement.price_impact_bps,
         });
     }
     Ok(())
@@ -122,9 +197,8 @@ pub struct Swap<'info> {
     #[account(seeds = [Config::SEED_PREFIX.as_bytes()], bump)]
     global_config: Box<Account<'info, Config>>,
 
-    /// CHECK: This account is verified by through the global config constraint
-    #[account(mut, constraint = global_config.fee_recipient == fee_recipient.key() @SwifeyError::IncorrectFeeRecipient)]
-    fee_recipient: AccountInfo<'info>,
+    #[account(mut, seeds = [FeeVault::SEED_PREFIX.as_bytes()], bump = fee_vault.bump)]
+    fee_vault: Box<Account<'info, FeeVault>>,
 
     #[account(mut, seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()], bump)]
     bonding_curve: Box<Account<'info, BondingCurve>>,
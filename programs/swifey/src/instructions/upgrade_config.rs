@@ -0,0 +1,88 @@
+use crate::{
+    errors::SwifeyError,
+    instructions::emit_config_initialized,
+    program::Swifey,
+    states::{Config, ConfigSettings, FeeVault, LegacyConfig},
+    utils::realloc_with_rent,
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+pub struct UpgradeConfig<'info> {
+    // Same gate as initialize: the legacy config could be rewritten by anyone, so its stored
+    // authority is not trusted
+    #[account(mut)]
+    admin: Signer<'info>,
+
+    /// CHECK: Legacy config account, its layout is checked before it is rewritten
+    #[account(
+        mut,
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump,
+        owner = crate::ID
+    )]
+    global_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [FeeVault::SEED_PREFIX.as_bytes()],
+        space = 8 + FeeVault::LEN,
+        bump
+    )]
+    fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ SwifeyError::UnauthorizedAddress)]
+    program: Program<'info, Swifey>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SwifeyError::UnauthorizedAddress)]
+    program_data: Box<Account<'info, ProgramData>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> UpgradeConfig<'info> {
+    // Initialize for a deployment whose config was created by the first version of the program:
+    // the existing account is grown to the current layout and rewritten with new_config.
+    // Legacy curves can be moved with upgrade_curve once this has run
+    pub fn process(&mut self, new_config: ConfigSettings, fee_vault_bump: u8) -> Result<()> {
+        let config_info = self.global_config.to_account_info();
+        let old_len = 8 + LegacyConfig::LEN;
+        let new_len = 8 + Config::LEN;
+
+        // 1. Only a config still in the legacy layout is upgraded
+        {
+            let data = config_info.try_borrow_data()?;
+            require!(
+                data.len() == old_len && data[..8] == Config::discriminator(),
+                SwifeyError::InvalidAccountLayout
+            );
+            LegacyConfig::deserialize(&mut &data[8..])?;
+        }
+
+        // 2. Validate configuration parameters
+        new_config.validate()?;
+
+        // 3. Grow the account, the admin covers the extra rent
+        realloc_with_rent(
+            &config_info,
+            &self.admin,
+            &self.system_program.to_account_info(),
+            old_len,
+            new_len,
+        )?;
+
+        let mut global_config = Config {
+            authority: new_config.authority,
+            pause_flags: new_config.pause_flags,
+            ..Config::default()
+        };
+        global_config.update(&new_config);
+        global_config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+        self.fee_vault.bump = fee_vault_bump;
+
+        emit_config_initialized(self.admin.key(), &new_config)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{
+    errors::SwifeyError,
+    states::{BondingCurve, Config, LegacyBondingCurve},
+    utils::{realloc_with_rent, CurveUpgraded},
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+use anchor_spl::token::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UpgradeCurve<'info> {
+    // Anyone can upgrade a curve, the result only depends on the config and the curve balances
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        seeds = [Config::SEED_PREFIX.as_bytes()],
+        bump
+    )]
+    global_config: Box<Account<'info, Config>>,
+
+    token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Legacy curve account, its layout is checked before it is read
+    #[account(
+        mut,
+        seeds = [BondingCurve::SEED_PREFIX.as_bytes(), &token_mint.key().to_bytes()],
+        bump,
+        owner = crate::ID
+    )]
+    bonding_curve: UncheckedAccount<'info>,
+
+    #[account(
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    curve_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = system_program::ID)]
+    system_program: Program<'info, System>,
+}
+
+impl<'info> UpgradeCurve<'info> {
+    // Move a curve launched by an earlier version of the program to the current layout, so
+    // swaps, migration and fee claims can load it again
+    pub fn process(&mut self) -> Result<()> {
+        let curve_info = self.bonding_curve.to_account_info();
+        let old_len = 8 + LegacyBondingCurve::LEN;
+        let new_len = 8 + BondingCurve::LEN;
+
+        // 1. Only curves still in the legacy layout are upgraded
+        let legacy = {
+            let data = curve_info.try_borrow_data()?;
+            require!(
+                data.len() == old_len && data[..8] == BondingCurve::discriminator(),
+                SwifeyError::InvalidAccountLayout
+            );
+            LegacyBondingCurve::deserialize(&mut &data[8..])?
+        };
+
+        // 2. Grow the account, the payer covers the extra rent
+        realloc_with_rent(
+            &curve_info,
+            &self.payer,
+            &self.system_program.to_account_info(),
+            old_len,
+            new_len,
+        )?;
+
+        // 3. Legacy reserves did not exclude rent, so recount them from the balances
+        let real_sol_reserve = curve_info
+            .lamports()
+            .checked_sub(Rent::get()?.minimum_balance(new_len))
+            .ok_or(SwifeyError::MathOverflow)?;
+        let real_token_reserve = self.curve_token_account.amount;
+
+        let bonding_curve = BondingCurve::from_legacy(
+            &legacy,
+            &self.global_config,
+            real_token_reserve,
+            real_sol_reserve,
+        );
+        bonding_curve.try_serialize(&mut &mut curve_info.try_borrow_mut_data()?[..])?;
+
+        emit!(CurveUpgraded {
+            token_mint: self.token_mint.key(),
+            payer: self.payer.key(),
+            real_sol_reserve,
+            real_token_reserve,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
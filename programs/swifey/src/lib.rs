@@ -15,16 +15,120 @@ declare_id!("EBQAXhr4mL1toiVA695mLaeXvHg4wLnnKJWmvQbMzjkz");
 pub mod swifey {
     use super::*;
 
+    pub fn initialize(ctx: Context<Initialize>, new_config: ConfigSettings) -> Result<()> {
+        let fee_vault_bump = ctx.bumps.fee_vault;
+        ctx.accounts.process(new_config, fee_vault_bump)
+    }
+
+    pub fn upgrade_config(ctx: Context<UpgradeConfig>, new_config: ConfigSettings) -> Result<()> {
+        let fee_vault_bump = ctx.bumps.fee_vault;
+        ctx.accounts.process(new_config, fee_vault_bump)
+    }
+
     pub fn configure(ctx: Context<Configure>, new_config: ConfigSettings) -> Result<()> {
-        ctx.accounts.process(new_config)
+        let bump = ctx.bumps.pending_config;
+        ctx.accounts.process(new_config, bump)
+    }
+
+    pub fn execute_config(ctx: Context<ExecuteConfig>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn cancel_config(ctx: Context<CancelConfig>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, pause_flags: PauseFlags) -> Result<()> {
+        ctx.accounts.process(pause_flags)
+    }
+
+    pub fn set_fees(ctx: Context<SetFees>, fees: FeeSettings) -> Result<()> {
+        let bump = ctx.bumps.pending_config;
+        ctx.accounts.process(fees, bump)
+    }
+
+    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>, fee_recipient: Pubkey) -> Result<()> {
+        ctx.accounts.process(fee_recipient)
+    }
+
+    pub fn set_curve_params(ctx: Context<SetCurveParams>, curve: CurveSettings) -> Result<()> {
+        let bump = ctx.bumps.pending_config;
+        ctx.accounts.process(curve, bump)
+    }
+
+    pub fn set_curve_status(ctx: Context<SetCurveStatus>, status: CurveStatus) -> Result<()> {
+        ctx.accounts.process(status)
+    }
+
+    pub fn start_unwind(ctx: Context<StartUnwind>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+        let bump = ctx.bumps.bonding_curve;
+        ctx.accounts.process(amount, bump)
+    }
+
+    pub fn set_role(ctx: Context<SetRole>, role: Role, account: Pubkey) -> Result<()> {
+        ctx.accounts.process(role, account)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.process(new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn cancel_authority(ctx: Context<CancelAuthority>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn create_multisig(ctx: Context<CreateMultisig>, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        let bump = ctx.bumps.multisig;
+        let signer_bump = ctx.bumps.multisig_signer;
+        ctx.accounts.process(approvers, threshold, bump, signer_bump)
+    }
+
+    pub fn set_multisig(ctx: Context<SetMultisig>, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        ctx.accounts.process(approvers, threshold)
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>
+    ) -> Result<()> {
+        let bump = ctx.bumps.proposal;
+        ctx.accounts.process(accounts, data, bump)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
+
+    pub fn configure_template(
+        ctx: Context<ConfigureTemplate>,
+        index: u16,
+        params: CurveParams,
+        is_active: bool
+    ) -> Result<()> {
+        let bump = ctx.bumps.curve_template;
+        ctx.accounts.process(index, params, is_active, bump)
     }
 
     pub fn launch<'info>(ctx: Context<'_, '_, '_, 'info, Launch<'info>>,
         name: String,
         symbol: String,
-        uri: String
+        uri: String,
+        template_index: Option<u16>
     ) -> Result<()> {
-        instructions::launch(ctx, name, symbol, uri)
+        instructions::launch(ctx, name, symbol, uri, template_index)
     }
 
     pub fn swap<'info>(
@@ -36,7 +140,33 @@ pub mod swifey {
         instructions::swap(ctx, amount, direction, min_out)
     }
 
-    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
-        Migrate::process(ctx)
+    pub fn swap_exact_out<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>, 
+        amount_out: u64, 
+        direction: u8, 
+        max_in: u64
+    ) -> Result<()> {
+        instructions::swap_exact_out(ctx, amount_out, direction, max_in)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        let bump = ctx.bumps.bonding_curve;
+        ctx.accounts.process(bump)
+    }
+
+    pub fn claim_fees(ctx: Context<ClaimFees>) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    pub fn migrate_clmm(ctx: Context<MigrateClmm>) -> Result<()> {
+        MigrateClmm::process(ctx)
+    }
+
+    pub fn migrate_cpmm(ctx: Context<MigrateCpmm>) -> Result<()> {
+        MigrateCpmm::process(ctx)
+    }
+
+    pub fn upgrade_curve(ctx: Context<UpgradeCurve>) -> Result<()> {
+        ctx.accounts.process()
     }
 }
\ No newline at end of file
@@ -2,18 +2,23 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, TokenAccount};
 
 use crate::errors::SwifeyError;
+use crate::states::{Config, FeeVault, MigrationTarget};
 use crate::utils::{
-    fixed_div_u128, fixed_mul_u128, sol_transfer_from_user, sol_transfer_with_signer, token_transfer_user, token_transfer_with_signer, CurveCompleted, TokenPurchased, TokenSold
+    calculate_fee_amount, fixed_div_u128, fixed_pow_u128, sol_transfer_from_user, sol_transfer_with_signer, token_transfer_user, token_transfer_with_signer, CurveCompleted, TokenPurchased, TokenSold
 };
 use crate::constants::{
     PRECISION, CRR_NUMERATOR, CRR_DENOMINATOR,
-    MIN_BUY_AMOUNT, FEE_PRECISION
+    MIN_BUY_AMOUNT, MIN_SELL_AMOUNT, FEE_PRECISION
 };
-use crate::states::Config;
 
 // Minimum SOL liquidity threshold (1 SOL)
 pub const MIN_SOL_LIQUIDITY: u64 = 1_000_000_000;  // 1 SOL in lamports
 
+// Upper bound on the rounding error of fixed_pow_u128 (in PRECISION units, and the same
+// fraction of the result), added to every power result so that buys and sells never pay out
+// more than the exact curve would
+const POW_ERROR_MARGIN: u128 = 100;
+
 // For reference:
 // - Total supply: 1,000,000,000.000000 tokens (1B with 6 decimals)
 // - Initial price: ~0.000005 SOL per token (5 SOL / 1B tokens)
@@ -22,13 +27,47 @@ pub const MIN_SOL_LIQUIDITY: u64 = 1_000_000_000;  // 1 SOL in lamports
 // - CRR: 0.651 (defined in constants.rs)
 // - All tokens start in virtual reserve for proper price discovery
 
+// Amounts settled by a swap, reported back to the swap instruction for its events
+pub struct SwapSettlement {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub creator_fee_amount: u64,
+    pub new_sol_reserves: u64,
+    pub new_token_reserves: u64,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub price_impact_bps: u64,
+    pub refund_amount: u64,
+    pub is_completed: bool,
+}
+
+// SOL and tokens of a completed curve, split for migration
+pub struct MigrationAmounts {
+    pub sol_amount: u64,    // Deposited into the pool
+    pub token_amount: u64,  // Deposited into the pool
+    pub migration_fee: u64, // keeper_reward + platform_fee
+    pub keeper_reward: u64,
+    pub platform_fee: u64,
+}
+
+// Trading state of a single curve, set by the authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveStatus {
+    Active, // Trades and migrates normally
+    Paused, // Swaps are halted, a completed curve can still migrate
+    Frozen, // Swaps and migration are halted
+    Unwinding, // Terminal: holders redeem tokens pro-rata for the real SOL reserve after unwind_at
+}
+
 #[account]
 pub struct BondingCurve {
     //Virtual reserves on the curve
     pub virtual_token_reserve: u64,
     pub virtual_sol_reserve: u64,
 
-    //Real reserves on the curve
+    //Real reserves on the curve: tokens held by the curve token account and SOL paid in by
+    //buyers, excluding rent and unclaimed creator fees
     pub real_token_reserve: u64,
     pub real_sol_reserve: u64,
 
@@ -41,13 +80,89 @@ pub struct BondingCurve {
     // New field to track if funds are migrated to Raydium
     pub is_migrated: bool,
 
+    // Curve parameters copied from Config at launch, so later config changes
+    // only apply to new launches
+    pub initial_virtual_sol_reserve: u64,
+    pub curve_limit: u64,
+    pub buy_fee_percentage: u64,       // Uses FEE_PRECISION (10000 = 100.00%)
+    pub sell_fee_percentage: u64,      // Uses FEE_PRECISION (10000 = 100.00%)
+    pub migration_fee_percentage: u64, // Uses FEE_PRECISION (10000 = 100.00%)
+    pub creator_fee_percentage: u64,   // Uses FEE_PRECISION (10000 = 100.00%)
+    pub migration_target: MigrationTarget,
+    pub status: CurveStatus,
+    pub unwind_at: i64, // Unix timestamp redemptions open at, 0 unless unwinding
+
+    // Token creator, entitled to the creator fee on every swap
+    pub creator: Pubkey,
+    // Creator fees held on the curve PDA and not yet claimed
+    pub creator_fees_accrued: u64,
+
     // Reserved field for padding
     pub reserved: [u8; 8]
 }
 
+// Layout of curves launched before their parameters were stored on the curve, brought to the
+// current layout by upgrade_curve
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyBondingCurve {
+    pub virtual_token_reserve: u64,
+    pub virtual_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub real_sol_reserve: u64,
+    pub token_total_supply: u64,
+    pub is_completed: bool,
+    pub is_migrated: bool,
+    pub reserved: [u8; 8]
+}
+
+impl LegacyBondingCurve {
+    pub const LEN: usize = 8 * 5 + 1 + 1 + 8;
+}
+
 impl<'info> BondingCurve {
     pub const SEED_PREFIX: &'static str = "bonding_curve";
-    pub const LEN: usize = 8 * 5 + 1 + 1 + 8;  // Back to original size
+    pub const LEN: usize = 8 * 5 + // reserves and total supply
+        1 + // is_completed
+        1 + // is_migrated
+        8 * 6 + // curve parameters
+        1 + // migration_target
+        1 + // status
+        8 + // unwind_at
+        32 + // creator
+        8 + // creator_fees_accrued
+        8; // reserved
+
+    // Current layout of a legacy curve. Those curves traded on the live config, so its current
+    // parameters are frozen on them. Their creator was never recorded, so they charge no creator
+    // fee. The real reserves are taken from the balances the curve holds
+    pub fn from_legacy(
+        legacy: &LegacyBondingCurve,
+        config: &Config,
+        real_token_reserve: u64,
+        real_sol_reserve: u64,
+    ) -> Self {
+        Self {
+            virtual_token_reserve: legacy.virtual_token_reserve,
+            virtual_sol_reserve: legacy.virtual_sol_reserve,
+            real_token_reserve,
+            real_sol_reserve,
+            token_total_supply: legacy.token_total_supply,
+            is_completed: legacy.is_completed,
+            is_migrated: legacy.is_migrated,
+            initial_virtual_sol_reserve: config.initial_virtual_sol_reserve,
+            curve_limit: config.curve_limit,
+            buy_fee_percentage: config.buy_fee_percentage,
+            sell_fee_percentage: config.sell_fee_percentage,
+            migration_fee_percentage: config.migration_fee_percentage,
+            creator_fee_percentage: 0,
+            migration_target: config.migration_target,
+            status: CurveStatus::Active,
+            unwind_at: 0,
+            creator: Pubkey::default(),
+            creator_fees_accrued: 0,
+            reserved: legacy.reserved,
+        }
+    }
 
     //Get signer for bonding curve PDA
     pub fn get_signer<'a>(mint: &'a Pubkey, bump: &'a u8) -> [&'a [u8]; 3] {
@@ -59,10 +174,10 @@ impl<'info> BondingCurve {
     }
 
     //Update reserves with minimum liquidity check
-    pub fn update_reserves(&mut self, reserve_lamport: u64, reserve_token: u64, initial_sol_reserve: u64) -> Result<bool> {
+    pub fn update_reserves(&mut self, reserve_lamport: u64, reserve_token: u64) -> Result<bool> {
         // Check minimum SOL liquidity threshold
         require!(
-            reserve_lamport >= initial_sol_reserve,
+            reserve_lamport >= self.initial_virtual_sol_reserve,
             SwifeyError::InsufficientLiquidity
         );
 
@@ -72,6 +187,86 @@ impl<'info> BondingCurve {
         Ok(true)
     }
 
+    // Move the real reserves by a settled swap
+    fn update_real_reserves(&mut self, sol_in: u64, sol_out: u64, tokens_in: u64, tokens_out: u64) -> Result<()> {
+        self.real_sol_reserve = self.real_sol_reserve
+            .checked_add(sol_in)
+            .and_then(|v| v.checked_sub(sol_out))
+            .ok_or(SwifeyError::InsufficientSolBalance)?;
+        self.real_token_reserve = self.real_token_reserve
+            .checked_add(tokens_in)
+            .and_then(|v| v.checked_sub(tokens_out))
+            .ok_or(SwifeyError::InsufficientTokenBalance)?;
+        Ok(())
+    }
+
+    // Solvency invariant, run at the end of every instruction that moves curve funds
+    pub fn check_solvency(&self, curve_pda: &AccountInfo<'info>, curve_ata: &AccountInfo<'info>) -> Result<()> {
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(curve_pda.data_len());
+        let curve_token_balance = {
+            let data = curve_ata.try_borrow_data()?;
+            TokenAccount::try_deserialize(&mut &data[..])?.amount
+        };
+
+        self.verify_solvency(curve_pda.lamports(), rent_exempt_minimum, curve_token_balance)
+    }
+
+    fn verify_solvency(&self, curve_lamports: u64, rent_exempt_minimum: u64, curve_token_balance: u64) -> Result<()> {
+        // 1. The curve PDA stays rent exempt while holding the real SOL reserve and unclaimed
+        // creator fees. Balances may exceed the tracked reserves (rent buffer, direct transfers),
+        // but never fall short
+        let required_lamports = self.real_sol_reserve
+            .checked_add(self.creator_fees_accrued)
+            .and_then(|v| v.checked_add(rent_exempt_minimum))
+            .ok_or(SwifeyError::MathOverflow)?;
+        require!(curve_lamports >= required_lamports, SwifeyError::CurveInsolvent);
+
+        // 2. The curve token account holds at least the real token reserve
+        require!(curve_token_balance >= self.real_token_reserve, SwifeyError::CurveInsolvent);
+
+        // 3. Selling every outstanding token back at the current curve price is covered by the
+        // real SOL reserve
+        let outstanding_tokens = self.token_total_supply
+            .checked_sub(self.real_token_reserve)
+            .ok_or(SwifeyError::CurveInsolvent)?;
+        // An unwinding curve pays out pro-rata instead, which the real SOL reserve always covers
+        if outstanding_tokens > 0 && !self.is_migrated && self.status != CurveStatus::Unwinding {
+            let (buyback_sol, _) = self.calculate_amount_out_preview(outstanding_tokens, 1, 0)?;
+            require!(buyback_sol <= self.real_sol_reserve, SwifeyError::CurveInsolvent);
+        }
+
+        Ok(())
+    }
+
+    // Take back tokens from a holder of an unwinding curve, and return the SOL they redeem for:
+    // their share of the real SOL reserve among all outstanding tokens
+    pub fn redeem(&mut self, amount: u64, now: i64) -> Result<u64> {
+        require!(now >= self.unwind_at, SwifeyError::UnwindNotReady);
+
+        let outstanding_tokens = self.token_total_supply
+            .checked_sub(self.real_token_reserve)
+            .ok_or(SwifeyError::MathOverflow)?;
+        require!(amount > 0, SwifeyError::DustAmount);
+        require!(amount <= outstanding_tokens, SwifeyError::InsufficientTokenBalance);
+
+        let sol_amount = (self.real_sol_reserve as u128)
+            .checked_mul(amount as u128)
+            .and_then(|v| v.checked_div(outstanding_tokens as u128))
+            .ok_or(SwifeyError::MathOverflow)?;
+        let sol_amount = u64::try_from(sol_amount).map_err(|_| SwifeyError::MathOverflow)?;
+        require!(sol_amount > 0, SwifeyError::DustAmount);
+
+        // Redeemed tokens go back to the curve, so later holders keep the same share per token
+        self.real_sol_reserve = self.real_sol_reserve
+            .checked_sub(sol_amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+        self.real_token_reserve = self.real_token_reserve
+            .checked_add(amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        Ok(sol_amount)
+    }
+
     // Helper to validate state transitions
     pub fn validate_state_transition(&self) -> Result<()> {
         // Prevent operations if already migrated
@@ -80,8 +275,8 @@ impl<'info> BondingCurve {
     }
 
     // Helper to safely update completion state
-    pub fn update_completion_state(&mut self, new_sol_reserves: u64, curve_limit: u64) -> Result<bool> {
-        let is_completed = if new_sol_reserves >= curve_limit {
+    pub fn update_completion_state(&mut self, new_sol_reserves: u64) -> Result<bool> {
+        let is_completed = if new_sol_reserves >= self.curve_limit {
             self.is_completed = true;
             true
         } else {
@@ -90,6 +285,24 @@ impl<'info> BondingCurve {
         Ok(is_completed)
     }
 
+    // SOL a buy can still add before the curve reaches curve_limit
+    pub fn remaining_capacity(&self) -> Result<u64> {
+        let remaining_capacity = self.curve_limit.saturating_sub(self.virtual_sol_reserve);
+        require!(remaining_capacity > 0, SwifeyError::CurveLimitReached);
+        Ok(remaining_capacity)
+    }
+
+    // Total SOL a buyer pays for amount_in, fees included
+    fn buy_cost(&self, amount_in: u64) -> Result<u64> {
+        let fee_amount = Self::calculate_fee(amount_in, self.buy_fee_percentage)?;
+        let creator_fee_amount = Self::calculate_fee(amount_in, self.creator_fee_percentage)?;
+
+        amount_in
+            .checked_add(fee_amount)
+            .and_then(|v| v.checked_add(creator_fee_amount))
+            .ok_or(error!(SwifeyError::MathOverflow))
+    }
+
     // Helper to safely update migration state
     pub fn update_migration_state(&mut self) -> Result<()> {
         require!(self.is_completed, SwifeyError::CurveNotCompleted);
@@ -99,17 +312,66 @@ impl<'info> BondingCurve {
         Ok(())
     }
 
+    // Split the real reserves into the pool deposit, the keeper reward and the platform fee
+    pub fn migration_amounts(&self, keeper_reward_percentage: u64) -> Result<MigrationAmounts> {
+        // Rent and unclaimed creator fees stay on the curve PDA
+        let sol_balance = self.real_sol_reserve;
+        require!(sol_balance > 0, SwifeyError::InsufficientSolBalance);
+
+        // Migration fee in basis points using the fee frozen at launch
+        let migration_fee = calculate_fee_amount(sol_balance, self.migration_fee_percentage)?;
+
+        // The keeper reward is carved out of the migration fee, the rest goes to the fee vault
+        let keeper_reward = calculate_fee_amount(migration_fee, keeper_reward_percentage)?;
+        let platform_fee = migration_fee
+            .checked_sub(keeper_reward)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        let sol_amount = sol_balance
+            .checked_sub(migration_fee)
+            .ok_or(SwifeyError::InsufficientSolBalance)?;
+
+        Ok(MigrationAmounts {
+            sol_amount,
+            token_amount: self.real_token_reserve,
+            migration_fee,
+            keeper_reward,
+            platform_fee,
+        })
+    }
+
+    // The real reserves left the curve with the pool deposit
+    pub fn complete_migration(&mut self) -> Result<()> {
+        self.real_sol_reserve = 0;
+        self.real_token_reserve = 0;
+        self.update_migration_state()
+    }
+
     // Calculate amount out without modifying state
     pub fn calculate_amount_out_preview(&self, amount_in: u64, direction: u8, fee_percentage: u64) -> Result<(u64, u64)> {
-        require!(self.virtual_sol_reserve > 0, SwifeyError::DivisionByZero);
-        require!(self.virtual_token_reserve > 0, SwifeyError::DivisionByZero);
-
         // Check minimum amounts
         if direction == 0 {
             require!(amount_in >= MIN_BUY_AMOUNT, SwifeyError::DustAmount);
         }
 
-        let mut amount_out = if direction == 0 { // Buying tokens
+        let amount_out = self.calculate_curve_output(amount_in, direction)?;
+
+        // Calculate fee amount using fixed-point arithmetic
+        let fee_amount = if direction == 0 {
+            Self::calculate_fee(amount_in, fee_percentage)?
+        } else {
+            Self::calculate_fee(amount_out, fee_percentage)?
+        };
+
+        Ok((amount_out, fee_amount))
+    }
+
+    // Curve output for amount_in, before fees and without the minimum amount checks
+    fn calculate_curve_output(&self, amount_in: u64, direction: u8) -> Result<u64> {
+        require!(self.virtual_sol_reserve > 0, SwifeyError::DivisionByZero);
+        require!(self.virtual_token_reserve > 0, SwifeyError::DivisionByZero);
+
+        let amount_out = if direction == 0 { // Buying tokens
             // Calculate using CRR formula: tokens_out = total_tokens * (1 - (current_sol/new_sol)^CRR)
             let current_sol = self.virtual_sol_reserve;
             let new_sol = current_sol.checked_add(amount_in)
@@ -121,21 +383,9 @@ impl<'info> BondingCurve {
             // Calculate CRR (as a fraction of PRECISION)
             let crr = fixed_div_u128(CRR_NUMERATOR, CRR_DENOMINATOR)?;
             
-            // Approximate the power function for values close to 1 using this formula:
-            // (1 + x)^a ≈ 1 + ax for x close to 0, where x = ratio - 1
-            // In our case, ratio is current_sol/new_sol which is less than 1
-            // So we use: ratio^CRR ≈ 1 - CRR*(1 - ratio)
-            let one_minus_ratio = PRECISION.checked_sub(ratio)
-                .ok_or(SwifeyError::MathOverflow)?;
-            
-            let crr_effect = (crr as u128)
-                .checked_mul(one_minus_ratio)
-                .ok_or(SwifeyError::MathOverflow)?
-                .checked_div(PRECISION)
-                .ok_or(SwifeyError::DivisionByZero)?;
-            
-            let final_ratio = PRECISION.checked_sub(crr_effect)
-                .ok_or(SwifeyError::MathOverflow)?;
+            // Evaluate ratio^CRR through e^(CRR * ln(ratio)), rounded up in favor of the curve
+            let final_ratio = Self::round_up_power(fixed_pow_u128(ratio, crr)?)?
+                .min(PRECISION);
             
             // Calculate tokens_out = total_tokens * (1 - final_ratio)
             let tokens_out = (self.virtual_token_reserve as u128)
@@ -147,7 +397,7 @@ impl<'info> BondingCurve {
             
             tokens_out
         } else { // Selling tokens
-            // For selling, use similar approximation but in reverse
+            // Inverse of the buy formula: sol_out = total_sol * (1 - (current_token/new_token)^(1/CRR))
             let current_token = self.virtual_token_reserve;
             let new_token = current_token.checked_add(amount_in)
                 .ok_or(SwifeyError::MathOverflow)?;
@@ -158,59 +408,151 @@ impl<'info> BondingCurve {
             // Calculate inverse CRR
             let inverse_crr = fixed_div_u128(CRR_DENOMINATOR, CRR_NUMERATOR)?;
             
-            // Use the same approximation
-            let one_minus_ratio = PRECISION.checked_sub(ratio)
-                .ok_or(SwifeyError::MathOverflow)?;
+            // Evaluate ratio^(1/CRR) through e^(ln(ratio) / CRR), rounded up in favor of the curve
+            let final_ratio = Self::round_up_power(fixed_pow_u128(ratio, inverse_crr)?)?
+                .min(PRECISION);
             
-            let crr_effect = (inverse_crr as u128)
-                .checked_mul(one_minus_ratio)
-                .ok_or(SwifeyError::MathOverflow)?
-                .checked_div(PRECISION)
-                .ok_or(SwifeyError::DivisionByZero)?;
-            
-            let final_ratio = PRECISION.checked_sub(crr_effect)
-                .ok_or(SwifeyError::MathOverflow)?;
-            
-            // Calculate base sol_out = total_sol * (1 - final_ratio)
-            let base_sol_out = (self.virtual_sol_reserve as u128)
+            // Calculate sol_out = total_sol * (1 - final_ratio)
+            let sol_out = (self.virtual_sol_reserve as u128)
                 .checked_mul(PRECISION.checked_sub(final_ratio)
                     .ok_or(SwifeyError::MathOverflow)?)
                 .ok_or(SwifeyError::MathOverflow)?
                 .checked_div(PRECISION)
                 .ok_or(SwifeyError::DivisionByZero)? as u64;
 
-            // Calculate total amount needed including fee
-            let total_sol_out = base_sol_out;
-            
-            total_sol_out
+            sol_out
         };
 
-        // Calculate fee amount using fixed-point arithmetic
-        let fee_amount = if direction == 0 {
-            (amount_in as u128)
-                .checked_mul(fee_percentage as u128)
+        Ok(amount_out)
+    }
+
+    // Calculate the input required for an exact output without modifying state.
+    // Buys return the SOL to send to the curve and the fee charged on top of it.
+    // Sells take the SOL the user should receive after fees and return the tokens to sell and the fee.
+    pub fn calculate_amount_in_preview(&self, amount_out: u64, direction: u8, fee_percentage: u64) -> Result<(u64, u64)> {
+        require!(self.virtual_sol_reserve > 0, SwifeyError::DivisionByZero);
+        require!(self.virtual_token_reserve > 0, SwifeyError::DivisionByZero);
+
+        if direction == 0 { // Buying exactly amount_out tokens
+            require!(amount_out < self.virtual_token_reserve, SwifeyError::InsufficientLiquidity);
+
+            // Invert tokens_out = total_tokens * (1 - (current_sol/new_sol)^CRR):
+            // sol_in = current_sol * ((current_token/new_token)^(1/CRR) - 1)
+            let current_token = self.virtual_token_reserve;
+            let new_token = current_token.checked_sub(amount_out)
+                .ok_or(SwifeyError::MathOverflow)?;
+
+            let ratio = fixed_div_u128(current_token, new_token)?;
+            let inverse_crr = fixed_div_u128(CRR_DENOMINATOR, CRR_NUMERATOR)?;
+
+            // Round the growth factor up in favor of the curve
+            let growth = Self::round_up_power(fixed_pow_u128(ratio, inverse_crr)?)?;
+
+            let sol_in = Self::mul_growth_ceil(self.virtual_sol_reserve, growth)?;
+            require!(sol_in >= MIN_BUY_AMOUNT, SwifeyError::DustAmount);
+
+            // Fee is charged on top of the SOL sent to the curve, exactly as in buy
+            let fee_amount = Self::calculate_fee(sol_in, fee_percentage)?;
+
+            Ok((sol_in, fee_amount))
+        } else { // Selling tokens for exactly amount_out SOL after fees
+            require!(fee_percentage < FEE_PRECISION, SwifeyError::InvalidFeePercentage);
+
+            // Gross SOL taken from the curve so that gross - fee(gross) covers amount_out
+            let net_precision = (FEE_PRECISION - fee_percentage) as u128;
+            let gross_sol_out = (amount_out as u128)
+                .checked_mul(FEE_PRECISION as u128)
                 .ok_or(SwifeyError::MathOverflow)?
-                .checked_div(FEE_PRECISION as u128)
-                .ok_or(SwifeyError::DivisionByZero)? as u64
-        } else {
-            (amount_out as u128)
-                .checked_mul(fee_percentage as u128)
+                .checked_add(net_precision - 1)
                 .ok_or(SwifeyError::MathOverflow)?
-                .checked_div(FEE_PRECISION as u128)
-                .ok_or(SwifeyError::DivisionByZero)? as u64
-        };
+                / net_precision;
+            let gross_sol_out = u64::try_from(gross_sol_out)
+                .map_err(|_| error!(SwifeyError::MathOverflow))?;
+            require!(gross_sol_out < self.virtual_sol_reserve, SwifeyError::InsufficientLiquidity);
 
-        Ok((amount_out, fee_amount))
+            // Invert sol_out = total_sol * (1 - (current_token/new_token)^(1/CRR)):
+            // tokens_in = current_token * ((current_sol/new_sol)^CRR - 1)
+            let current_sol = self.virtual_sol_reserve;
+            let new_sol = current_sol.checked_sub(gross_sol_out)
+                .ok_or(SwifeyError::MathOverflow)?;
+
+            let ratio = fixed_div_u128(current_sol, new_sol)?;
+            let crr = fixed_div_u128(CRR_NUMERATOR, CRR_DENOMINATOR)?;
+
+            // Round the growth factor up in favor of the curve
+            let growth = Self::round_up_power(fixed_pow_u128(ratio, crr)?)?;
+
+            let tokens_in = Self::mul_growth_ceil(self.virtual_token_reserve, growth)?;
+            require!(tokens_in >= MIN_SELL_AMOUNT, SwifeyError::DustAmount);
+
+            // The user keeps exactly amount_out, everything above it is fee
+            let fee_amount = gross_sol_out.checked_sub(amount_out)
+                .ok_or(SwifeyError::MathOverflow)?;
+
+            Ok((tokens_in, fee_amount))
+        }
+    }
+
+    // Price impact of moving the virtual reserves to the new reserves, in basis points.
+    // The spot price is proportional to sol_reserve / token_reserve, so the CRR cancels out
+    pub fn calculate_price_impact(&self, new_sol_reserves: u64, new_token_reserves: u64) -> Result<u64> {
+        require!(self.virtual_token_reserve > 0 && new_token_reserves > 0, SwifeyError::DivisionByZero);
+
+        // price_after / price_before = (new_sol * old_token) / (old_sol * new_token)
+        let price_after = (new_sol_reserves as u128)
+            .checked_mul(self.virtual_token_reserve as u128)
+            .ok_or(SwifeyError::MathOverflow)?;
+        let price_before = (self.virtual_sol_reserve as u128)
+            .checked_mul(new_token_reserves as u128)
+            .ok_or(SwifeyError::MathOverflow)?;
+        require!(price_before > 0, SwifeyError::DivisionByZero);
+
+        let impact = price_after.abs_diff(price_before)
+            .checked_mul(FEE_PRECISION as u128)
+            .ok_or(SwifeyError::MathOverflow)?
+            / price_before;
+
+        u64::try_from(impact).map_err(|_| error!(SwifeyError::MathOverflow))
+    }
+
+    // Pad a fixed_pow_u128 result by its error bound, both relative and absolute
+    fn round_up_power(value: u128) -> Result<u128> {
+        let relative_margin = value
+            .checked_mul(POW_ERROR_MARGIN)
+            .ok_or(SwifeyError::MathOverflow)?
+            / PRECISION;
+
+        value
+            .checked_add(relative_margin)
+            .and_then(|v| v.checked_add(POW_ERROR_MARGIN))
+            .ok_or(error!(SwifeyError::MathOverflow))
+    }
+
+    // reserve * (growth - 1), rounded up, where growth is scaled by PRECISION
+    fn mul_growth_ceil(reserve: u64, growth: u128) -> Result<u64> {
+        let amount = (reserve as u128)
+            .checked_mul(growth.checked_sub(PRECISION)
+                .ok_or(SwifeyError::MathOverflow)?)
+            .ok_or(SwifeyError::MathOverflow)?
+            .checked_add(PRECISION - 1)
+            .ok_or(SwifeyError::MathOverflow)?
+            / PRECISION;
+
+        u64::try_from(amount).map_err(|_| error!(SwifeyError::MathOverflow))
+    }
+
+    // Fee on an amount using FEE_PRECISION (10000 = 100.00%)
+    pub fn calculate_fee(amount: u64, fee_percentage: u64) -> Result<u64> {
+        calculate_fee_amount(amount, fee_percentage)
     }
 
     // Swap sol for tokens
     pub fn buy(
         &mut self,
         token_mint: &Account<'info, Mint>,
-        config: &Account<'info, Config>,
         user: &Signer<'info>,
         curve_pda: &mut AccountInfo<'info>,
-        fee_recipient: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
         user_ata: &mut AccountInfo<'info>,
         curve_ata: &AccountInfo<'info>,
         amount_in: u64,
@@ -218,39 +560,150 @@ impl<'info> BondingCurve {
         curve_bump: u8,
         system_program: &AccountInfo<'info>,
         token_program: &AccountInfo<'info>,
-    ) -> Result<(u64, u64, u64, u64, u64, bool)> {
+    ) -> Result<SwapSettlement> {
         // Validate state before proceeding
         self.validate_state_transition()?;
 
-        // Calculate fee to be added on top of amount_in
-        let fee_amount = if config.buy_fee_percentage > 0 {
-            (amount_in as u128)
-                .checked_mul(config.buy_fee_percentage as u128)
+        // A buy crossing curve_limit only fills up to the limit
+        let requested_amount_in = amount_in;
+        let amount_in = requested_amount_in.min(self.remaining_capacity()?);
+
+        // Calculate platform and creator fees to be added on top of amount_in
+        let fee_amount = Self::calculate_fee(amount_in, self.buy_fee_percentage)?;
+        let creator_fee_amount = Self::calculate_fee(amount_in, self.creator_fee_percentage)?;
+
+        // Calculate amounts using the original amount_in (not including fees)
+        let (amount_out, min_amount_out) = if amount_in < requested_amount_in {
+            // Scale the minimum output to the filled portion so the buyer's limit price still holds
+            let scaled_min_amount_out = (min_amount_out as u128)
+                .checked_mul(amount_in as u128)
                 .ok_or(SwifeyError::MathOverflow)?
-                .checked_div(FEE_PRECISION as u128)
-                .ok_or(SwifeyError::DivisionByZero)? as u64
+                / requested_amount_in as u128;
+            (self.calculate_curve_output(amount_in, 0)?, scaled_min_amount_out as u64)
         } else {
-            0
+            (self.calculate_amount_out_preview(amount_in, 0, 0)?.0, min_amount_out)
         };
-        
-        // Total amount user will pay is amount_in + fee_amount
-        let total_amount_in = amount_in.checked_add(fee_amount)
+
+        // Validate minimum output
+        require!(
+            amount_out >= min_amount_out,
+            SwifeyError::InsufficientAmountOut
+        );
+
+        // SOL and fees for the unfilled part are never taken from the buyer
+        let refund_amount = self.buy_cost(requested_amount_in)?
+            .checked_sub(self.buy_cost(amount_in)?)
             .ok_or(SwifeyError::MathOverflow)?;
 
-        // Validate user has enough SOL to cover amount_in + fees
-        let user_balance = user.lamports();
+        self.settle_buy(
+            token_mint,
+            user,
+            curve_pda,
+            fee_vault,
+            user_ata,
+            curve_ata,
+            amount_in,
+            amount_out,
+            fee_amount,
+            creator_fee_amount,
+            refund_amount,
+            curve_bump,
+            system_program,
+            token_program,
+        )
+    }
+
+    // Swap sol for exactly amount_out tokens
+    pub fn buy_exact_out(
+        &mut self,
+        token_mint: &Account<'info, Mint>,
+        user: &Signer<'info>,
+        curve_pda: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
+        user_ata: &mut AccountInfo<'info>,
+        curve_ata: &AccountInfo<'info>,
+        amount_out: u64,
+        max_amount_in: u64,
+        curve_bump: u8,
+        system_program: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<SwapSettlement> {
+        // Validate state before proceeding
+        self.validate_state_transition()?;
+
+        // Calculate the SOL required for amount_out and the fees on top of it
+        let (amount_in, fee_amount) = self.buy_exact_out_preview(amount_out)?;
+        let creator_fee_amount = Self::calculate_fee(amount_in, self.creator_fee_percentage)?;
+
+        // Validate maximum input, including the fees the user pays on top
+        let total_amount_in = amount_in
+            .checked_add(fee_amount)
+            .and_then(|v| v.checked_add(creator_fee_amount))
+            .ok_or(SwifeyError::MathOverflow)?;
         require!(
-            user_balance >= total_amount_in,
-            SwifeyError::InsufficientUserBalance
+            total_amount_in <= max_amount_in,
+            SwifeyError::SlippageExceeded
         );
 
-        // Calculate amounts using the original amount_in (not including fees)
-        let (amount_out, _) = self.calculate_amount_out_preview(amount_in, 0, 0)?;
+        self.settle_buy(
+            token_mint,
+            user,
+            curve_pda,
+            fee_vault,
+            user_ata,
+            curve_ata,
+            amount_in,
+            amount_out,
+            fee_amount,
+            creator_fee_amount,
+            0, // Exact-out buys are filled completely or not at all
+            curve_bump,
+            system_program,
+            token_program,
+        )
+    }
 
-        // Validate minimum output
+    // SOL and fee for an exact-out buy, which must fill completely below curve_limit.
+    // Unlike buy, it is never partially filled since the user asked for an exact amount
+    pub fn buy_exact_out_preview(&self, amount_out: u64) -> Result<(u64, u64)> {
+        let (amount_in, fee_amount) =
+            self.calculate_amount_in_preview(amount_out, 0, self.buy_fee_percentage)?;
         require!(
-            amount_out >= min_amount_out,
-            SwifeyError::InsufficientAmountOut
+            amount_in <= self.remaining_capacity()?,
+            SwifeyError::CurveLimitReached
+        );
+        Ok((amount_in, fee_amount))
+    }
+
+    // Move funds and update reserves for a priced buy
+    fn settle_buy(
+        &mut self,
+        token_mint: &Account<'info, Mint>,
+        user: &Signer<'info>,
+        curve_pda: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
+        user_ata: &mut AccountInfo<'info>,
+        curve_ata: &AccountInfo<'info>,
+        amount_in: u64,
+        amount_out: u64,
+        fee_amount: u64,
+        creator_fee_amount: u64,
+        refund_amount: u64,
+        curve_bump: u8,
+        system_program: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<SwapSettlement> {
+        // Total amount user will pay is amount_in + fee_amount + creator_fee_amount
+        let total_amount_in = amount_in
+            .checked_add(fee_amount)
+            .and_then(|v| v.checked_add(creator_fee_amount))
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        // Validate user has enough SOL to cover amount_in + fees
+        let user_balance = user.lamports();
+        require!(
+            user_balance >= total_amount_in,
+            SwifeyError::InsufficientUserBalance
         );
 
         // Calculate new reserves using original amount_in
@@ -262,9 +715,21 @@ impl<'info> BondingCurve {
             .checked_sub(amount_out)
             .ok_or(SwifeyError::MathOverflow)?;
 
-        // Perform transfers - user pays total_amount_in (amount_in + fee_amount)
-        sol_transfer_from_user(&user, fee_recipient, system_program, fee_amount)?;
-        sol_transfer_from_user(&user, curve_pda, system_program, amount_in)?;
+        // The curve token account must actually hold the tokens being bought
+        require!(amount_out <= self.real_token_reserve, SwifeyError::InsufficientLiquidity);
+
+        let price_impact_bps = self.calculate_price_impact(new_sol_reserves, new_token_reserves)?;
+
+        // Perform transfers - user pays total_amount_in, the platform fee accrues in the fee vault
+        // and the creator fee accrues on the curve PDA
+        sol_transfer_from_user(&user, &fee_vault.to_account_info(), system_program, fee_amount)?;
+        fee_vault.accrue(token_mint.key(), fee_amount)?;
+        sol_transfer_from_user(
+            &user,
+            curve_pda,
+            system_program,
+            amount_in.checked_add(creator_fee_amount).ok_or(SwifeyError::MathOverflow)?,
+        )?;
         token_transfer_with_signer(
             curve_ata,
             curve_pda,
@@ -274,11 +739,15 @@ impl<'info> BondingCurve {
             amount_out,
         )?;
 
+        self.accrue_creator_fee(creator_fee_amount)?;
+
         // Update reserves
-        self.update_reserves(new_sol_reserves, new_token_reserves, config.initial_virtual_sol_reserve)?;
+        self.update_reserves(new_sol_reserves, new_token_reserves)?;
+        self.update_real_reserves(amount_in, 0, 0, amount_out)?;
+        self.check_solvency(curve_pda, curve_ata)?;
 
         // Check if curve is completed
-        let is_completed = self.update_completion_state(new_sol_reserves, config.curve_limit)?;
+        let is_completed = self.update_completion_state(new_sol_reserves)?;
 
         if is_completed {
             emit!(CurveCompleted {
@@ -294,37 +763,153 @@ impl<'info> BondingCurve {
             sol_amount: amount_in,
             token_amount: amount_out,
             fee_amount: fee_amount,
+            creator: self.creator,
+            creator_fee_amount,
             price: new_sol_reserves / new_token_reserves,
             new_sol_reserves,
-            new_token_reserves
+            new_token_reserves,
+            real_sol_reserve: self.real_sol_reserve,
+            real_token_reserve: self.real_token_reserve,
+            price_impact_bps,
+            refund_amount,
         });
 
-        Ok((amount_in, amount_out, fee_amount, new_sol_reserves, new_token_reserves, is_completed))
+        Ok(SwapSettlement {
+            amount_in,
+            amount_out,
+            fee_amount,
+            creator_fee_amount,
+            new_sol_reserves,
+            new_token_reserves,
+            real_sol_reserve: self.real_sol_reserve,
+            real_token_reserve: self.real_token_reserve,
+            price_impact_bps,
+            refund_amount,
+            is_completed,
+        })
     }
 
     // Swap tokens for sol
     pub fn sell(
         &mut self,
         token_mint: &Account<'info, Mint>,
-        config: &Account<'info, Config>,
         user: &Signer<'info>,
         curve_pda: &mut AccountInfo<'info>,
         user_ata: &mut AccountInfo<'info>,
-        fee_recipient: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
         curve_ata: &mut AccountInfo<'info>,
         amount_in: u64,
         min_amount_out: u64,
         curve_bump: u8,
         system_program: &AccountInfo<'info>,
         token_program: &AccountInfo<'info>,
-    ) -> Result<(u64, u64, u64, u64, u64)> {
+    ) -> Result<SwapSettlement> {
         // Validate state before proceeding
         self.validate_state_transition()?;
 
         // Calculate amounts and fees
         let (amount_out, fee_amount) =
-            self.calculate_amount_out_preview(amount_in, 1, config.sell_fee_percentage)?;
+            self.calculate_amount_out_preview(amount_in, 1, self.sell_fee_percentage)?;
+        let creator_fee_amount = Self::calculate_fee(amount_out, self.creator_fee_percentage)?;
+
+        // Validate minimum output received by the user after the sell and creator fees. The
+        // first version compared min_amount_out with the output before fees, so the same value
+        // now sets a tighter bound, matching what sell_exact_out guarantees
+        require!(
+            amount_out.saturating_sub(fee_amount).saturating_sub(creator_fee_amount) >= min_amount_out,
+            SwifeyError::InsufficientAmountOut
+        );
+
+        self.settle_sell(
+            token_mint,
+            user,
+            curve_pda,
+            user_ata,
+            fee_vault,
+            curve_ata,
+            amount_in,
+            amount_out,
+            fee_amount,
+            creator_fee_amount,
+            curve_bump,
+            system_program,
+            token_program,
+        )
+    }
 
+    // Swap tokens for exactly amount_out sol, received after fees
+    pub fn sell_exact_out(
+        &mut self,
+        token_mint: &Account<'info, Mint>,
+        user: &Signer<'info>,
+        curve_pda: &mut AccountInfo<'info>,
+        user_ata: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
+        curve_ata: &mut AccountInfo<'info>,
+        amount_out: u64,
+        max_amount_in: u64,
+        curve_bump: u8,
+        system_program: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<SwapSettlement> {
+        // Validate state before proceeding
+        self.validate_state_transition()?;
+
+        // Calculate the tokens required and the total fee taken from the gross SOL output
+        let total_fee_percentage = self.sell_fee_percentage
+            .checked_add(self.creator_fee_percentage)
+            .ok_or(SwifeyError::MathOverflow)?;
+        let (amount_in, total_fee_amount) =
+            self.calculate_amount_in_preview(amount_out, 1, total_fee_percentage)?;
+
+        // Validate maximum token input
+        require!(
+            amount_in <= max_amount_in,
+            SwifeyError::SlippageExceeded
+        );
+
+        let gross_amount_out = amount_out.checked_add(total_fee_amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        // Split the fee the same way a regular sell does, rounding leftovers to the platform
+        let creator_fee_amount = Self::calculate_fee(gross_amount_out, self.creator_fee_percentage)?;
+        let fee_amount = total_fee_amount.checked_sub(creator_fee_amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        self.settle_sell(
+            token_mint,
+            user,
+            curve_pda,
+            user_ata,
+            fee_vault,
+            curve_ata,
+            amount_in,
+            gross_amount_out,
+            fee_amount,
+            creator_fee_amount,
+            curve_bump,
+            system_program,
+            token_program,
+        )
+    }
+
+    // Move funds and update reserves for a priced sell
+    fn settle_sell(
+        &mut self,
+        token_mint: &Account<'info, Mint>,
+        user: &Signer<'info>,
+        curve_pda: &mut AccountInfo<'info>,
+        user_ata: &mut AccountInfo<'info>,
+        fee_vault: &mut Account<'info, FeeVault>,
+        curve_ata: &mut AccountInfo<'info>,
+        amount_in: u64,
+        amount_out: u64,
+        fee_amount: u64,
+        creator_fee_amount: u64,
+        curve_bump: u8,
+        system_program: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<SwapSettlement> {
         // Calculate new reserves
         let new_token_reserves = self.virtual_token_reserve
             .checked_add(amount_in)
@@ -334,13 +919,22 @@ impl<'info> BondingCurve {
             .checked_sub(amount_out)
             .ok_or(SwifeyError::MathOverflow)?;
 
+        let price_impact_bps = self.calculate_price_impact(new_sol_reserves, new_token_reserves)?;
+
         // Calculate user amount (amount minus fees)
-        let user_amount = amount_out.checked_sub(fee_amount)
+        let user_amount = amount_out
+            .checked_sub(fee_amount)
+            .and_then(|v| v.checked_sub(creator_fee_amount))
             .ok_or(SwifeyError::MathOverflow)?;
 
-        // Validate SOL balance - check if we have enough for both user amount and fees
+        // Validate SOL balance - the SOL paid in by buyers must cover the gross amount out, and the
+        // curve must hold both the user amount and the platform fee
+        require!(amount_out <= self.real_sol_reserve, SwifeyError::InsufficientSolBalance);
         let pda_sol_balance = curve_pda.lamports();
-        require!(pda_sol_balance >= user_amount, SwifeyError::InsufficientSolBalance);
+        require!(
+            pda_sol_balance >= user_amount.checked_add(fee_amount).ok_or(SwifeyError::MathOverflow)?,
+            SwifeyError::InsufficientSolBalance
+        );
 
         let token = token_mint.key();
         let signer_seeds: &[&[&[u8]]] = &[&BondingCurve::get_signer(&token, &curve_bump)];
@@ -357,19 +951,23 @@ impl<'info> BondingCurve {
             user_amount,
         )?;
 
-        // Transfer fees to fee recipient if there are any and if we have enough balance
-        if fee_amount > 0 && pda_sol_balance.checked_sub(user_amount).unwrap_or(0) >= fee_amount {
-            sol_transfer_with_signer(
-                curve_pda, 
-                fee_recipient,
-                system_program,
-                signer_seeds,
-                fee_amount,
-            )?;
-        }
+        // Transfer the platform fee to the fee vault
+        sol_transfer_with_signer(
+            curve_pda, 
+            &fee_vault.to_account_info(),
+            system_program,
+            signer_seeds,
+            fee_amount,
+        )?;
+        fee_vault.accrue(token_mint.key(), fee_amount)?;
+
+        // The creator fee stays on the curve PDA until claimed
+        self.accrue_creator_fee(creator_fee_amount)?;
 
         // Update reserves
-        self.update_reserves(new_sol_reserves, new_token_reserves, config.initial_virtual_sol_reserve)?;
+        self.update_reserves(new_sol_reserves, new_token_reserves)?;
+        self.update_real_reserves(0, amount_out, amount_in, 0)?;
+        self.check_solvency(curve_pda, curve_ata)?;
 
         emit!(TokenSold {
             token_mint: token_mint.key(),
@@ -377,11 +975,436 @@ impl<'info> BondingCurve {
             sol_amount: user_amount,
             token_amount: amount_in,
             fee_amount,
+            creator: self.creator,
+            creator_fee_amount,
             price: new_sol_reserves / new_token_reserves,
             new_sol_reserves,
-            new_token_reserves
+            new_token_reserves,
+            real_sol_reserve: self.real_sol_reserve,
+            real_token_reserve: self.real_token_reserve,
+            price_impact_bps,
         });
 
-        Ok((amount_in, amount_out, fee_amount, new_sol_reserves, new_token_reserves))
+        Ok(SwapSettlement {
+            amount_in,
+            amount_out,
+            fee_amount,
+            creator_fee_amount,
+            new_sol_reserves,
+            new_token_reserves,
+            real_sol_reserve: self.real_sol_reserve,
+            real_token_reserve: self.real_token_reserve,
+            price_impact_bps,
+            refund_amount: 0,
+            is_completed: false,
+        })
+    }
+
+    // Track creator fees held on the curve PDA until the creator claims them
+    fn accrue_creator_fee(&mut self, creator_fee_amount: u64) -> Result<()> {
+        self.creator_fees_accrued = self.creator_fees_accrued
+            .checked_add(creator_fee_amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{INITIAL_SOL_RESERVE, TARGET_SOL_AMOUNT};
+    use crate::utils::test_utils::{assert_close, next_random};
+
+    const TOTAL_SUPPLY: u64 = 1_000_000_000_000_000; // 1B tokens with 6 decimals
+
+    fn curve(virtual_sol_reserve: u64, virtual_token_reserve: u64) -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserve,
+            virtual_sol_reserve,
+            real_token_reserve: 0,
+            real_sol_reserve: 0,
+            token_total_supply: TOTAL_SUPPLY,
+            is_completed: false,
+            is_migrated: false,
+            initial_virtual_sol_reserve: INITIAL_SOL_RESERVE,
+            curve_limit: TARGET_SOL_AMOUNT,
+            buy_fee_percentage: 0,
+            sell_fee_percentage: 0,
+            migration_fee_percentage: 0,
+            creator_fee_percentage: 0,
+            migration_target: MigrationTarget::RaydiumClmm,
+            status: CurveStatus::Active,
+            unwind_at: 0,
+            creator: Pubkey::default(),
+            creator_fees_accrued: 0,
+            reserved: [0; 8],
+        }
+    }
+
+    // The power error is bounded relative to the ratio, so it scales with the reserve (or the
+    // amount itself when an exact-output quote grows past the reserve)
+    fn reserve_tolerance(reserve: u64) -> f64 {
+        (reserve as f64 * 1e-9).max(1.0)
+    }
+
+    #[test]
+    fn test_buy_preview_matches_f64_curve() {
+        let crr = CRR_NUMERATOR as f64 / CRR_DENOMINATOR as f64;
+        let mut seed = 0x0123_4567_89ab_cdef;
+        for _ in 0..2000 {
+            // Any SOL reserve between the initial reserve and the curve target
+            let sol_reserve = INITIAL_SOL_RESERVE + next_random(&mut seed) % (TARGET_SOL_AMOUNT - INITIAL_SOL_RESERVE);
+            let token_reserve = TOTAL_SUPPLY / 10 + next_random(&mut seed) % (TOTAL_SUPPLY - TOTAL_SUPPLY / 10);
+            // Buys from dust up to 10x the target, so very large orders are covered
+            let amount_in = MIN_BUY_AMOUNT + next_random(&mut seed) % (TARGET_SOL_AMOUNT * 10);
+
+            let (tokens_out, _) = curve(sol_reserve, token_reserve)
+                .calculate_amount_out_preview(amount_in, 0, 0)
+                .unwrap();
+
+            let ratio = sol_reserve as f64 / (sol_reserve as f64 + amount_in as f64);
+            let expected = token_reserve as f64 * (1.0 - ratio.powf(crr));
+            assert_close(tokens_out as f64, expected, 0.0, reserve_tolerance(token_reserve));
+        }
+    }
+
+    #[test]
+    fn test_sell_preview_matches_f64_curve() {
+        let inverse_crr = CRR_DENOMINATOR as f64 / CRR_NUMERATOR as f64;
+        let mut seed = 0xfedc_ba98_7654_3210;
+        for _ in 0..2000 {
+            let sol_reserve = INITIAL_SOL_RESERVE + next_random(&mut seed) % (TARGET_SOL_AMOUNT - INITIAL_SOL_RESERVE);
+            let token_reserve = TOTAL_SUPPLY / 10 + next_random(&mut seed) % (TOTAL_SUPPLY - TOTAL_SUPPLY / 10);
+            // Sells from dust up to the full supply
+            let amount_in = 1_000 + next_random(&mut seed) % TOTAL_SUPPLY;
+
+            let (sol_out, _) = curve(sol_reserve, token_reserve)
+                .calculate_amount_out_preview(amount_in, 1, 0)
+                .unwrap();
+
+            let ratio = token_reserve as f64 / (token_reserve as f64 + amount_in as f64);
+            let expected = sol_reserve as f64 * (1.0 - ratio.powf(inverse_crr));
+            assert_close(sol_out as f64, expected, 0.0, reserve_tolerance(sol_reserve));
+        }
+    }
+
+    #[test]
+    fn test_buy_then_sell_never_returns_more_sol() {
+        let mut seed = 0x1357_9bdf_0246_8ace;
+        for _ in 0..500 {
+            let sol_reserve = INITIAL_SOL_RESERVE + next_random(&mut seed) % (TARGET_SOL_AMOUNT - INITIAL_SOL_RESERVE);
+            let amount_in = MIN_BUY_AMOUNT + next_random(&mut seed) % TARGET_SOL_AMOUNT;
+            let mut bonding_curve = curve(sol_reserve, TOTAL_SUPPLY);
+
+            let (tokens_out, _) = bonding_curve.calculate_amount_out_preview(amount_in, 0, 0).unwrap();
+            bonding_curve.virtual_sol_reserve += amount_in;
+            bonding_curve.virtual_token_reserve -= tokens_out;
+
+            let (sol_out, _) = bonding_curve.calculate_amount_out_preview(tokens_out, 1, 0).unwrap();
+            assert!(sol_out <= amount_in, "sol_out={} amount_in={}", sol_out, amount_in);
+        }
+    }
+
+    #[test]
+    fn test_buy_exact_out_inverts_curve() {
+        let inverse_crr = CRR_DENOMINATOR as f64 / CRR_NUMERATOR as f64;
+        let mut seed = 0x0f0f_0f0f_1234_5678;
+        for _ in 0..2000 {
+            let sol_reserve = INITIAL_SOL_RESERVE + next_random(&mut seed) % (TARGET_SOL_AMOUNT - INITIAL_SOL_RESERVE);
+            let token_reserve = TOTAL_SUPPLY / 10 + next_random(&mut seed) % (TOTAL_SUPPLY - TOTAL_SUPPLY / 10);
+            // Up to 99% of the token reserve
+            let amount_out = 1_000_000 + next_random(&mut seed) % (token_reserve / 100 * 99);
+
+            let (sol_in, fee_amount) = match curve(sol_reserve, token_reserve)
+                .calculate_amount_in_preview(amount_out, 0, 100)
+            {
+                Ok(result) => result,
+                Err(_) => continue, // below the dust threshold
+            };
+
+            let ratio = token_reserve as f64 / (token_reserve as f64 - amount_out as f64);
+            let expected = sol_reserve as f64 * (ratio.powf(inverse_crr) - 1.0);
+            assert!(sol_in as f64 >= expected.floor(), "sol_in={} expected={}", sol_in, expected);
+            assert_close(sol_in as f64, expected, 0.0, reserve_tolerance(sol_in.max(sol_reserve)));
+            assert_eq!(fee_amount, BondingCurve::calculate_fee(sol_in, 100).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sell_exact_out_inverts_curve() {
+        let crr = CRR_NUMERATOR as f64 / CRR_DENOMINATOR as f64;
+        let sell_fee = 100; // 1%
+        let mut seed = 0x7777_8888_9999_aaaa;
+        for _ in 0..2000 {
+            let sol_reserve = INITIAL_SOL_RESERVE + next_random(&mut seed) % (TARGET_SOL_AMOUNT - INITIAL_SOL_RESERVE);
+            let token_reserve = TOTAL_SUPPLY / 10 + next_random(&mut seed) % (TOTAL_SUPPLY - TOTAL_SUPPLY / 10);
+            let amount_out = 1_000 + next_random(&mut seed) % (sol_reserve / 2);
+
+            let (tokens_in, fee_amount) = curve(sol_reserve, token_reserve)
+                .calculate_amount_in_preview(amount_out, 1, sell_fee)
+                .unwrap();
+
+            // The fee taken from the gross output is at least the regular sell fee
+            let gross_sol_out = amount_out + fee_amount;
+            assert!(fee_amount >= BondingCurve::calculate_fee(gross_sol_out, sell_fee).unwrap());
+            assert!(fee_amount <= BondingCurve::calculate_fee(gross_sol_out, sell_fee).unwrap() + 1);
+
+            let ratio = sol_reserve as f64 / (sol_reserve as f64 - gross_sol_out as f64);
+            let expected = token_reserve as f64 * (ratio.powf(crr) - 1.0);
+            assert!(tokens_in as f64 >= expected.floor(), "tokens_in={} expected={}", tokens_in, expected);
+            assert_close(tokens_in as f64, expected, 0.0, reserve_tolerance(tokens_in.max(token_reserve)));
+        }
+    }
+
+    #[test]
+    fn test_real_reserves_follow_swaps() {
+        let mut bonding_curve = curve(INITIAL_SOL_RESERVE, TOTAL_SUPPLY);
+        bonding_curve.real_token_reserve = TOTAL_SUPPLY;
+
+        // Buy 2 SOL worth of tokens, then sell half of them back
+        bonding_curve.update_real_reserves(2_000_000_000, 0, 0, 1_000_000).unwrap();
+        assert_eq!(bonding_curve.real_sol_reserve, 2_000_000_000);
+        assert_eq!(bonding_curve.real_token_reserve, TOTAL_SUPPLY - 1_000_000);
+
+        bonding_curve.update_real_reserves(0, 900_000_000, 500_000, 0).unwrap();
+        assert_eq!(bonding_curve.real_sol_reserve, 1_100_000_000);
+        assert_eq!(bonding_curve.real_token_reserve, TOTAL_SUPPLY - 500_000);
+
+        // The curve can never pay out more SOL or tokens than it took in
+        assert!(bonding_curve.update_real_reserves(0, 1_100_000_001, 0, 0).is_err());
+        assert!(bonding_curve.update_real_reserves(0, 0, 0, TOTAL_SUPPLY).is_err());
+    }
+
+    #[test]
+    fn test_verify_solvency_checks_balances() {
+        let rent_exempt_minimum = 2_000_000;
+        let mut bonding_curve = curve(INITIAL_SOL_RESERVE, TOTAL_SUPPLY);
+        bonding_curve.real_sol_reserve = 5_000_000_000;
+        bonding_curve.real_token_reserve = TOTAL_SUPPLY; // nothing outstanding yet
+        bonding_curve.creator_fees_accrued = 10_000_000;
+
+        let required_lamports = 5_000_000_000 + 10_000_000 + rent_exempt_minimum;
+        assert!(bonding_curve
+            .verify_solvency(required_lamports, rent_exempt_minimum, TOTAL_SUPPLY)
+            .is_ok());
+
+        // Extra lamports or tokens sent straight to the curve are tolerated
+        assert!(bonding_curve
+            .verify_solvency(required_lamports + 1, rent_exempt_minimum, TOTAL_SUPPLY + 1)
+            .is_ok());
+
+        // Any shortfall is not
+        assert!(bonding_curve
+            .verify_solvency(required_lamports - 1, rent_exempt_minimum, TOTAL_SUPPLY)
+            .is_err());
+        assert!(bonding_curve
+            .verify_solvency(required_lamports, rent_exempt_minimum, TOTAL_SUPPLY - 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_solvency_covers_buyback() {
+        let mut bonding_curve = curve(INITIAL_SOL_RESERVE, TOTAL_SUPPLY);
+        bonding_curve.real_token_reserve = TOTAL_SUPPLY;
+
+        // Buy in several steps, moving reserves exactly as settle_buy does
+        for amount_in in [1_000_000_000, 7_500_000_000, 123_456_789, 20_000_000_000] {
+            let (amount_out, _) = bonding_curve.calculate_amount_out_preview(amount_in, 0, 0).unwrap();
+            let new_sol = bonding_curve.virtual_sol_reserve + amount_in;
+            let new_token = bonding_curve.virtual_token_reserve - amount_out;
+            bonding_curve.update_reserves(new_sol, new_token).unwrap();
+            bonding_curve.update_real_reserves(amount_in, 0, 0, amount_out).unwrap();
+
+            let lamports = bonding_curve.real_sol_reserve;
+            assert!(bonding_curve.verify_solvency(lamports, 0, bonding_curve.real_token_reserve).is_ok());
+        }
+
+        // Tokens appearing in circulation without SOL backing them break solvency
+        bonding_curve.real_token_reserve -= TOTAL_SUPPLY / 100;
+        let lamports = bonding_curve.real_sol_reserve;
+        assert!(bonding_curve.verify_solvency(lamports, 0, TOTAL_SUPPLY).is_err());
+    }
+
+    #[test]
+    fn test_price_impact() {
+        let bonding_curve = curve(INITIAL_SOL_RESERVE, TOTAL_SUPPLY);
+
+        // No change in reserves, no impact
+        assert_eq!(bonding_curve.calculate_price_impact(INITIAL_SOL_RESERVE, TOTAL_SUPPLY).unwrap(), 0);
+
+        // Doubling the SOL reserve doubles the price: +100%
+        assert_eq!(bonding_curve.calculate_price_impact(INITIAL_SOL_RESERVE * 2, TOTAL_SUPPLY).unwrap(), 10_000);
+
+        // Doubling the token reserve halves the price: -50%
+        assert_eq!(bonding_curve.calculate_price_impact(INITIAL_SOL_RESERVE, TOTAL_SUPPLY * 2).unwrap(), 5_000);
+
+        // A real buy moves sol / token by (new_sol / old_sol)^(1 + CRR) - 1
+        let amount_in = INITIAL_SOL_RESERVE / 1000;
+        let (amount_out, _) = bonding_curve.calculate_amount_out_preview(amount_in, 0, 0).unwrap();
+        let impact = bonding_curve
+            .calculate_price_impact(INITIAL_SOL_RESERVE + amount_in, TOTAL_SUPPLY - amount_out)
+            .unwrap();
+        let crr = CRR_NUMERATOR as f64 / CRR_DENOMINATOR as f64;
+        let expected = ((1.001f64).powf(1.0 + crr) - 1.0) * 10_000.0;
+        assert!((impact as f64 - expected).abs() <= 1.0, "impact={} expected={}", impact, expected);
+    }
+
+    #[test]
+    fn test_partial_fill_capacity() {
+        let mut bonding_curve = curve(TARGET_SOL_AMOUNT - 2_000_000_000, TOTAL_SUPPLY / 2);
+        bonding_curve.buy_fee_percentage = 100; // 1%
+        bonding_curve.creator_fee_percentage = 50; // 0.5%
+
+        // Only 2 SOL fit below the limit, a 5 SOL buy is refunded 3 SOL plus the fees on it
+        let filled = 5_000_000_000u64.min(bonding_curve.remaining_capacity().unwrap());
+        assert_eq!(filled, 2_000_000_000);
+        let refund = bonding_curve.buy_cost(5_000_000_000).unwrap() - bonding_curve.buy_cost(filled).unwrap();
+        assert_eq!(refund, 3_000_000_000 + 30_000_000 + 15_000_000);
+
+        // The last few lamports of capacity can still be filled, even below the minimum buy
+        bonding_curve.virtual_sol_reserve = TARGET_SOL_AMOUNT - 10;
+        assert_eq!(bonding_curve.remaining_capacity().unwrap(), 10);
+        assert!(bonding_curve.calculate_curve_output(10, 0).is_ok());
+
+        // A curve at its limit has no capacity left
+        bonding_curve.virtual_sol_reserve = TARGET_SOL_AMOUNT;
+        assert!(bonding_curve.remaining_capacity().is_err());
+    }
+
+    #[test]
+    fn test_exact_out_rejects_dust() {
+        // Same minimums as swap, applied to the input priced for the exact output
+        let bonding_curve = curve(INITIAL_SOL_RESERVE, TOTAL_SUPPLY);
+        assert_eq!(
+            bonding_curve.calculate_amount_in_preview(1, 0, 0).unwrap_err(),
+            SwifeyError::DustAmount.into()
+        );
+
+        // Few tokens against a large SOL reserve, so 1000 lamports cost less than MIN_SELL_AMOUNT
+        let bonding_curve = curve(INITIAL_SOL_RESERVE, 1_000_000_000);
+        assert_eq!(
+            bonding_curve.calculate_amount_in_preview(1_000, 1, 0).unwrap_err(),
+            SwifeyError::DustAmount.into()
+        );
+    }
+
+    #[test]
+    fn test_buy_exact_out_never_partially_fills() {
+        let bonding_curve = curve(TARGET_SOL_AMOUNT - 2_000_000_000, TOTAL_SUPPLY / 2);
+
+        // Tokens bought with 1 SOL fit below the limit
+        let (fits, _) = bonding_curve.calculate_amount_out_preview(1_000_000_000, 0, 0).unwrap();
+        let (amount_in, _) = bonding_curve.buy_exact_out_preview(fits).unwrap();
+        assert!(amount_in <= 2_000_000_000);
+
+        // Tokens that would take 5 SOL cross it and are rejected instead of filled in part
+        let (crosses, _) = bonding_curve.calculate_amount_out_preview(5_000_000_000, 0, 0).unwrap();
+        assert_eq!(
+            bonding_curve.buy_exact_out_preview(crosses).unwrap_err(),
+            SwifeyError::CurveLimitReached.into()
+        );
+    }
+
+    #[test]
+    fn test_upgrade_legacy_curve() {
+        let legacy = LegacyBondingCurve {
+            virtual_token_reserve: TOTAL_SUPPLY / 2,
+            virtual_sol_reserve: INITIAL_SOL_RESERVE * 2,
+            real_token_reserve: 0,
+            real_sol_reserve: 1_234,
+            token_total_supply: TOTAL_SUPPLY,
+            is_completed: false,
+            is_migrated: false,
+            reserved: [0; 8],
+        };
+        let data = legacy.try_to_vec().unwrap();
+        assert_eq!(data.len(), LegacyBondingCurve::LEN);
+
+        let config = Config {
+            curve_limit: TARGET_SOL_AMOUNT,
+            initial_virtual_sol_reserve: INITIAL_SOL_RESERVE,
+            buy_fee_percentage: 100,
+            sell_fee_percentage: 200,
+            migration_fee_percentage: 300,
+            creator_fee_percentage: 50,
+            migration_target: MigrationTarget::RaydiumCpmm,
+            ..Config::default()
+        };
+        let legacy = LegacyBondingCurve::deserialize(&mut &data[..]).unwrap();
+        let bonding_curve = BondingCurve::from_legacy(&legacy, &config, TOTAL_SUPPLY / 2, 5_000_000_000);
+
+        // Reserves come from the legacy curve and its balances, parameters from the config
+        assert_eq!(bonding_curve.virtual_sol_reserve, INITIAL_SOL_RESERVE * 2);
+        assert_eq!(bonding_curve.real_token_reserve, TOTAL_SUPPLY / 2);
+        assert_eq!(bonding_curve.real_sol_reserve, 5_000_000_000);
+        assert_eq!(bonding_curve.curve_limit, TARGET_SOL_AMOUNT);
+        assert_eq!(bonding_curve.sell_fee_percentage, 200);
+        assert_eq!(bonding_curve.migration_target, MigrationTarget::RaydiumCpmm);
+        assert_eq!(bonding_curve.status, CurveStatus::Active);
+
+        // No creator was recorded, so no creator fee is charged
+        assert_eq!(bonding_curve.creator_fee_percentage, 0);
+        assert_eq!(bonding_curve.try_to_vec().unwrap().len(), BondingCurve::LEN);
+    }
+
+    #[test]
+    fn test_migration_amounts() {
+        let mut bonding_curve = curve(TARGET_SOL_AMOUNT, TOTAL_SUPPLY / 5);
+        bonding_curve.is_completed = true;
+        bonding_curve.real_sol_reserve = 80_000_000_000;
+        bonding_curve.real_token_reserve = TOTAL_SUPPLY / 5;
+        bonding_curve.migration_fee_percentage = 500; // 5%
+
+        // 10% of the 4 SOL migration fee goes to the keeper
+        let amounts = bonding_curve.migration_amounts(1_000).unwrap();
+        assert_eq!(amounts.migration_fee, 4_000_000_000);
+        assert_eq!(amounts.keeper_reward, 400_000_000);
+        assert_eq!(amounts.platform_fee, 3_600_000_000);
+        assert_eq!(amounts.sol_amount, 76_000_000_000);
+        assert_eq!(amounts.token_amount, TOTAL_SUPPLY / 5);
+
+        bonding_curve.complete_migration().unwrap();
+        assert!(bonding_curve.is_migrated);
+        assert_eq!(bonding_curve.real_sol_reserve, 0);
+        assert_eq!(bonding_curve.real_token_reserve, 0);
+
+        // Nothing left to migrate, and a curve migrates only once
+        assert!(bonding_curve.migration_amounts(1_000).is_err());
+        assert!(bonding_curve.complete_migration().is_err());
+    }
+
+    #[test]
+    fn test_redeem_pro_rata() {
+        let mut bonding_curve = curve(TARGET_SOL_AMOUNT, TOTAL_SUPPLY / 2);
+        bonding_curve.status = CurveStatus::Unwinding;
+        bonding_curve.real_sol_reserve = 30_000_000_000;
+        bonding_curve.real_token_reserve = TOTAL_SUPPLY / 4; // 3/4 of the supply is outstanding
+        bonding_curve.unwind_at = 1_000;
+
+        // Every holder gets the same SOL per token, whatever the redemption order
+        let third = TOTAL_SUPPLY / 4;
+        assert_eq!(bonding_curve.redeem(third, 1_000).unwrap(), 10_000_000_000);
+        assert_eq!(bonding_curve.redeem(third, 1_000).unwrap(), 10_000_000_000);
+        assert_eq!(bonding_curve.redeem(third, 1_000).unwrap(), 10_000_000_000);
+        assert_eq!(bonding_curve.real_sol_reserve, 0);
+        assert_eq!(bonding_curve.real_token_reserve, TOTAL_SUPPLY);
+
+        // Nothing is outstanding anymore
+        assert!(bonding_curve.redeem(1, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_redeem_before_unwind_at() {
+        let mut bonding_curve = curve(TARGET_SOL_AMOUNT, TOTAL_SUPPLY / 2);
+        bonding_curve.status = CurveStatus::Unwinding;
+        bonding_curve.real_sol_reserve = 30_000_000_000;
+        bonding_curve.unwind_at = 1_000;
+
+        assert_eq!(
+            bonding_curve.redeem(1_000_000, 999).unwrap_err(),
+            SwifeyError::UnwindNotReady.into()
+        );
+        assert_eq!(bonding_curve.real_sol_reserve, 30_000_000_000);
+        assert!(bonding_curve.redeem(1_000_000, 1_000).is_ok());
     }
 }
@@ -1,5 +1,56 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{FEE_PRECISION, MAX_PRICE_IMPACT_BPS},
+    errors::SwifeyError,
+    states::CurveParams,
+};
+
+// What happens to the liquidity position NFT once a curve is migrated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LpPolicy {
+    Burn, // Burn the position NFT, the liquidity can never be withdrawn
+    Lock, // Move the position NFT to the LP lock PDA
+}
+
+// Pool type a completed curve migrates into, each one has its own migrate instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MigrationTarget {
+    RaydiumClmm, // Concentrated liquidity pool with a full-range position
+    RaydiumCpmm, // Constant-product pool
+}
+
+// Keys the authority can delegate a single admin action to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Pauser,     // May call set_pause
+    FeeManager, // May call set_fees
+    Migrator,   // Only key allowed to migrate when set, otherwise migration is permissionless
+}
+
+// Operations the pauser or authority halted, each one checked by its own instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PauseFlags {
+    pub launch: bool,
+    pub buy: bool,
+    pub sell: bool, // Keep false to let holders exit while buys are paused
+    pub migrate: bool,
+}
+
+impl PauseFlags {
+    pub const LEN: usize = 4;
+
+    // Swaps check the flag of their direction only (0 = buy, 1 = sell)
+    pub fn check_swap(&self, direction: u8) -> Result<()> {
+        if direction == 0 {
+            require!(!self.buy, SwifeyError::BuysPaused);
+        } else {
+            require!(!self.sell, SwifeyError::SellsPaused);
+        }
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ConfigSettings {  // New struct for the instruction argument
     pub authority: Pubkey,
@@ -7,13 +58,19 @@ pub struct ConfigSettings {  // New struct for the instruction argument
     pub curve_limit: u64,
     pub initial_virtual_token_reserve: u64,
     pub initial_virtual_sol_reserve: u64,
-    pub initial_real_token_reserve: u64,
     pub total_token_supply: u64,
     pub buy_fee_percentage: u64,     // Uses FEE_PRECISION (10000 = 100.00%)
     pub sell_fee_percentage: u64,    // Uses FEE_PRECISION (10000 = 100.00%)
     pub migration_fee_percentage: u64, // Uses FEE_PRECISION (10000 = 100.00%)
+    pub creator_fee_percentage: u64, // Uses FEE_PRECISION (10000 = 100.00%)
+    pub keeper_reward_percentage: u64, // Share of the migration fee paid to the migrator, uses FEE_PRECISION
+    pub lp_policy: LpPolicy,         // Applied to the liquidity position after migration
+    pub migration_target: MigrationTarget, // Frozen on each curve at launch
+    pub clmm_amm_config: Pubkey, // Raydium AMM config accepted by migrate_clmm
+    pub cpmm_amm_config: Pubkey, // Raydium AMM config accepted by migrate_cpmm
     pub max_price_impact: u64,  // Maximum allowed price impact (in basis points)
-    pub is_paused: bool,             // New pause flag
+    pub config_timelock: i64,   // Seconds before a queued config change can be executed
+    pub pause_flags: PauseFlags,
     pub reserved: [[u8; 8]; 8]
 }
 
@@ -25,55 +82,184 @@ impl Default for ConfigSettings {
             curve_limit: 0,
             initial_virtual_token_reserve: 0,
             initial_virtual_sol_reserve: 0,
-            initial_real_token_reserve: 0,
             total_token_supply: 0,
             buy_fee_percentage: 0,
             sell_fee_percentage: 0,
             migration_fee_percentage: 0,
-            max_price_impact: 10000, // Default to 100% (10000 basis points)
-            is_paused: false,
+            creator_fee_percentage: 0,
+            keeper_reward_percentage: 0,
+            lp_policy: LpPolicy::Lock,
+            migration_target: MigrationTarget::RaydiumClmm,
+            clmm_amm_config: Pubkey::default(),
+            cpmm_amm_config: Pubkey::default(),
+            max_price_impact: MAX_PRICE_IMPACT_BPS,
+            config_timelock: 0,
+            pause_flags: PauseFlags::default(),
             reserved: [[0; 8]; 8],
         }
     }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeSettings {  // Argument of set_fees
+    pub buy_fee_percentage: u64,     // Uses FEE_PRECISION (10000 = 100.00%)
+    pub sell_fee_percentage: u64,    // Uses FEE_PRECISION (10000 = 100.00%)
+    pub migration_fee_percentage: u64, // Uses FEE_PRECISION (10000 = 100.00%)
+    pub creator_fee_percentage: u64, // Uses FEE_PRECISION (10000 = 100.00%)
+    pub keeper_reward_percentage: u64, // Share of the migration fee paid to the migrator, uses FEE_PRECISION
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CurveSettings {  // Argument of set_curve_params
+    pub curve_limit: u64,
+    pub initial_virtual_token_reserve: u64,
+    pub initial_virtual_sol_reserve: u64,
+    pub total_token_supply: u64,
+}
+
 #[account]
 pub struct Config {
     pub authority: Pubkey, // Primary authority address
+    pub pending_authority: Pubkey, // Proposed authority, Pubkey::default() when none
+
+    // Role keys managed by the authority, Pubkey::default() when unassigned
+    pub pauser: Pubkey,
+    pub fee_manager: Pubkey,
+    pub migrator: Pubkey,
+
     pub fee_recipient: Pubkey, // Team wallet address
     pub curve_limit: u64, // Lamports to complete the bonding curve
 
     // Curve token/sol amount reserves
     pub initial_virtual_token_reserve: u64,
     pub initial_virtual_sol_reserve: u64,
-    pub initial_real_token_reserve: u64,
     pub total_token_supply: u64,
 
     // Fee percentages using FEE_PRECISION (10000 = 100.00%)
     pub buy_fee_percentage: u64,
     pub sell_fee_percentage: u64,
     pub migration_fee_percentage: u64,
+    pub creator_fee_percentage: u64, // Paid to the token creator on every swap
+    pub keeper_reward_percentage: u64, // Share of the migration fee paid to whoever migrates
+
+    pub lp_policy: LpPolicy, // Applied to the liquidity position after migration
+    pub migration_target: MigrationTarget, // Pool type for new launches, frozen on each curve
+
+    // Raydium AMM config of the pools created by each migration target, sets their fee tier
+    pub clmm_amm_config: Pubkey,
+    pub cpmm_amm_config: Pubkey,
 
     pub max_price_impact: u64,  // Maximum allowed price impact (in basis points)
-    pub is_paused: bool,             // New pause flag
+    pub config_timelock: i64,   // Seconds before a queued config change can be executed
+    pub pause_flags: PauseFlags,
+    pub reserved: [[u8; 8]; 8]
+}
+
+// Layout of the config created by the first version of the program, replaced by upgrade_config
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyConfig {
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub curve_limit: u64,
+    pub initial_virtual_token_reserve: u64,
+    pub initial_virtual_sol_reserve: u64,
+    pub initial_real_token_reserve: u64,
+    pub total_token_supply: u64,
+    pub buy_fee_percentage: u64,
+    pub sell_fee_percentage: u64,
+    pub migration_fee_percentage: u64,
+    pub max_price_impact: u64,
+    pub is_paused: bool,
     pub reserved: [[u8; 8]; 8]
 }
 
+impl LegacyConfig {
+    // As in the first version, counts the discriminator again on top of the 8 bytes of account space
+    pub const LEN: usize = 8 + // discriminator
+        32 * 2 + // authority, fee_recipient
+        8 * 9 + // curve parameters, fees and max_price_impact
+        1 + // is_paused
+        64; // reserved
+}
+
+impl ConfigSettings {
+    // Validation shared by initialize, upgrade_config and configure
+    pub fn validate(&self) -> Result<()> {
+        // A default authority could never sign, and fees claimed to the default pubkey would be lost
+        require!(self.authority != Pubkey::default(), SwifeyError::UnauthorizedAddress);
+        require!(self.fee_recipient != Pubkey::default(), SwifeyError::IncorrectFeeRecipient);
+
+        self.curve_params().validate()?;
+        require!(
+            self.keeper_reward_percentage <= FEE_PRECISION,
+            SwifeyError::InvalidFeePercentage
+        );
+        // Buy-side impact grows past 100% for buys that are large against the reserve, so only a
+        // zero limit, which would reject every swap, is invalid
+        require!(self.max_price_impact > 0, SwifeyError::InvalidPriceImpact);
+        require!(self.config_timelock >= 0, SwifeyError::IncorrectValueRange);
+        Ok(())
+    }
+
+    pub const LEN: usize = 32 + // authority
+        32 + // fee_recipient
+        8 + // curve_limit
+        8 + // initial_virtual_token_reserve
+        8 + // initial_virtual_sol_reserve
+        8 + // total_token_supply
+        8 + // buy_fee_percentage
+        8 + // sell_fee_percentage
+        8 + // migration_fee_percentage
+        8 + // creator_fee_percentage
+        8 + // keeper_reward_percentage
+        1 + // lp_policy
+        1 + // migration_target
+        32 + // clmm_amm_config
+        32 + // cpmm_amm_config
+        8 + // max_price_impact
+        8 + // config_timelock
+        PauseFlags::LEN + // pause_flags
+        64; // reserved
+
+    pub fn curve_params(&self) -> CurveParams {
+        CurveParams {
+            curve_limit: self.curve_limit,
+            initial_virtual_token_reserve: self.initial_virtual_token_reserve,
+            initial_virtual_sol_reserve: self.initial_virtual_sol_reserve,
+            total_token_supply: self.total_token_supply,
+            buy_fee_percentage: self.buy_fee_percentage,
+            sell_fee_percentage: self.sell_fee_percentage,
+            migration_fee_percentage: self.migration_fee_percentage,
+            creator_fee_percentage: self.creator_fee_percentage,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             authority: Pubkey::default(),
+            pending_authority: Pubkey::default(),
+            pauser: Pubkey::default(),
+            fee_manager: Pubkey::default(),
+            migrator: Pubkey::default(),
             fee_recipient: Pubkey::default(),
             curve_limit: 0,
             initial_virtual_token_reserve: 0,
             initial_virtual_sol_reserve: 0,
-            initial_real_token_reserve: 0,
             total_token_supply: 0,
             buy_fee_percentage: 0,
             sell_fee_percentage: 0,
             migration_fee_percentage: 0,
-            max_price_impact: 10000, // Default to 100% (10000 basis points)
-            is_paused: false,
+            creator_fee_percentage: 0,
+            keeper_reward_percentage: 0,
+            lp_policy: LpPolicy::Lock,
+            migration_target: MigrationTarget::RaydiumClmm,
+            clmm_amm_config: Pubkey::default(),
+            cpmm_amm_config: Pubkey::default(),
+            max_price_impact: MAX_PRICE_IMPACT_BPS,
+            config_timelock: 0,
+            pause_flags: PauseFlags::default(),
             reserved: [[0; 8]; 8],
         }
     }
@@ -81,18 +267,190 @@ impl Default for Config {
 
 impl Config {
     pub const SEED_PREFIX: &'static str = "global_config";
+
+    // Default curve parameters, used by launches that do not select a CurveTemplate
+    pub fn curve_params(&self) -> CurveParams {
+        self.settings().curve_params()
+    }
+
+    // The only Raydium AMM config a migration to target can create its pool with
+    pub fn amm_config(&self, target: MigrationTarget) -> Pubkey {
+        match target {
+            MigrationTarget::RaydiumClmm => self.clmm_amm_config,
+            MigrationTarget::RaydiumCpmm => self.cpmm_amm_config,
+        }
+    }
+
+    // The authority keeps every permission it delegates to a role
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        *key == self.authority || *key == self.pauser
+    }
+
+    pub fn can_set_fees(&self, key: &Pubkey) -> bool {
+        *key == self.authority || *key == self.fee_manager
+    }
+
+    pub fn can_migrate(&self, key: &Pubkey) -> bool {
+        self.migrator == Pubkey::default() || *key == self.migrator
+    }
+
+    pub fn role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Pauser => self.pauser,
+            Role::FeeManager => self.fee_manager,
+            Role::Migrator => self.migrator,
+        }
+    }
+
+    pub fn set_role(&mut self, role: Role, key: Pubkey) {
+        match role {
+            Role::Pauser => self.pauser = key,
+            Role::FeeManager => self.fee_manager = key,
+            Role::Migrator => self.migrator = key,
+        }
+    }
+
+    // Current settings, the base for a queued change of only some of them
+    pub fn settings(&self) -> ConfigSettings {
+        ConfigSettings {
+            authority: self.authority,
+            fee_recipient: self.fee_recipient,
+            curve_limit: self.curve_limit,
+            initial_virtual_token_reserve: self.initial_virtual_token_reserve,
+            initial_virtual_sol_reserve: self.initial_virtual_sol_reserve,
+            total_token_supply: self.total_token_supply,
+            buy_fee_percentage: self.buy_fee_percentage,
+            sell_fee_percentage: self.sell_fee_percentage,
+            migration_fee_percentage: self.migration_fee_percentage,
+            creator_fee_percentage: self.creator_fee_percentage,
+            keeper_reward_percentage: self.keeper_reward_percentage,
+            lp_policy: self.lp_policy,
+            migration_target: self.migration_target,
+            clmm_amm_config: self.clmm_amm_config,
+            cpmm_amm_config: self.cpmm_amm_config,
+            max_price_impact: self.max_price_impact,
+            config_timelock: self.config_timelock,
+            pause_flags: self.pause_flags,
+            reserved: self.reserved,
+        }
+    }
+
+    // Replace the timelocked settings. The authority, roles and pause flags have their own
+    // instructions and are left as is
+    pub fn update(&mut self, new_config: &ConfigSettings) {
+        self.fee_recipient = new_config.fee_recipient;
+        self.curve_limit = new_config.curve_limit;
+        self.initial_virtual_token_reserve = new_config.initial_virtual_token_reserve;
+        self.initial_virtual_sol_reserve = new_config.initial_virtual_sol_reserve;
+        self.total_token_supply = new_config.total_token_supply;
+        self.buy_fee_percentage = new_config.buy_fee_percentage;
+        self.sell_fee_percentage = new_config.sell_fee_percentage;
+        self.migration_fee_percentage = new_config.migration_fee_percentage;
+        self.creator_fee_percentage = new_config.creator_fee_percentage;
+        self.keeper_reward_percentage = new_config.keeper_reward_percentage;
+        self.lp_policy = new_config.lp_policy;
+        self.migration_target = new_config.migration_target;
+        self.clmm_amm_config = new_config.clmm_amm_config;
+        self.cpmm_amm_config = new_config.cpmm_amm_config;
+        self.max_price_impact = new_config.max_price_impact;
+        self.config_timelock = new_config.config_timelock;
+        self.reserved = new_config.reserved;
+    }
+
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
+        32 + // pending_authority
+        32 + // pauser
+        32 + // fee_manager
+        32 + // migrator
         32 + // fee_recipient
         8 + // curve_limit
         8 + // initial_virtual_token_reserve
         8 + // initial_virtual_sol_reserve
-        8 + // initial_real_token_reserve
         8 + // total_token_supply
         8 + // buy_fee_percentage
         8 + // sell_fee_percentage
         8 + // migration_fee_percentage
+        8 + // creator_fee_percentage
+        8 + // keeper_reward_percentage
+        1 + // lp_policy
+        1 + // migration_target
+        32 + // clmm_amm_config
+        32 + // cpmm_amm_config
         8 + // max_price_impact
-        1 + // is_paused
+        8 + // config_timelock
+        PauseFlags::LEN + // pause_flags
         64; // reserved
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::LAMPORTS_PER_SOL;
+
+    fn valid_settings() -> ConfigSettings {
+        ConfigSettings {
+            authority: Pubkey::new_unique(),
+            fee_recipient: Pubkey::new_unique(),
+            curve_limit: 80 * LAMPORTS_PER_SOL,
+            initial_virtual_token_reserve: 1_000_000_000_000_000,
+            initial_virtual_sol_reserve: 4 * LAMPORTS_PER_SOL,
+            total_token_supply: 1_000_000_000_000_000,
+            buy_fee_percentage: 100,
+            sell_fee_percentage: 100,
+            migration_fee_percentage: 100,
+            ..ConfigSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_max_price_impact() {
+        assert!(valid_settings().validate().is_ok());
+
+        let settings = ConfigSettings { max_price_impact: 0, ..valid_settings() };
+        assert_eq!(settings.validate().unwrap_err(), SwifeyError::InvalidPriceImpact.into());
+
+        // Limits above 100% are needed for large buys against a small reserve
+        for max_price_impact in [1, FEE_PRECISION, 10_000_000_000] {
+            let settings = ConfigSettings { max_price_impact, ..valid_settings() };
+            assert!(settings.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_overflowing_supply() {
+        let settings = ConfigSettings { total_token_supply: u64::MAX, ..valid_settings() };
+        assert_eq!(settings.validate().unwrap_err(), SwifeyError::MathOverflow.into());
+    }
+
+    #[test]
+    fn test_legacy_config_len() {
+        let legacy = LegacyConfig {
+            authority: Pubkey::new_unique(),
+            fee_recipient: Pubkey::new_unique(),
+            curve_limit: 80 * LAMPORTS_PER_SOL,
+            initial_virtual_token_reserve: 0,
+            initial_virtual_sol_reserve: 0,
+            initial_real_token_reserve: 0,
+            total_token_supply: 0,
+            buy_fee_percentage: 0,
+            sell_fee_percentage: 0,
+            migration_fee_percentage: 0,
+            max_price_impact: 10_000,
+            is_paused: false,
+            reserved: [[0; 8]; 8],
+        };
+
+        // The legacy LEN counts the discriminator twice, as the first version did
+        assert_eq!(legacy.try_to_vec().unwrap().len() + 8, LegacyConfig::LEN);
+    }
+
+    #[test]
+    fn test_validate_rejects_default_keys() {
+        let settings = ConfigSettings { authority: Pubkey::default(), ..valid_settings() };
+        assert_eq!(settings.validate().unwrap_err(), SwifeyError::UnauthorizedAddress.into());
+
+        let settings = ConfigSettings { fee_recipient: Pubkey::default(), ..valid_settings() };
+        assert_eq!(settings.validate().unwrap_err(), SwifeyError::IncorrectFeeRecipient.into());
+    }
+}
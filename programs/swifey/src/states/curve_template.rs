@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::SwifeyError, constants::{FEE_PRECISION, LAMPORTS_PER_SOL}};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct CurveParams {
+    pub curve_limit: u64, // Lamports to complete the bonding curve
+
+    // Curve token/sol amount reserves
+    pub initial_virtual_token_reserve: u64,
+    pub initial_virtual_sol_reserve: u64,
+    pub total_token_supply: u64,
+
+    // Fee percentages using FEE_PRECISION (10000 = 100.00%)
+    pub buy_fee_percentage: u64,
+    pub sell_fee_percentage: u64,
+    pub migration_fee_percentage: u64,
+    pub creator_fee_percentage: u64, // Paid to the token creator on every swap
+}
+
+impl CurveParams {
+    pub const LEN: usize = 8 * 8;
+
+    // Validate the curve-shaping parameters shared by Config and every CurveTemplate
+    pub fn validate(&self) -> Result<()> {
+        // 1. Validate fee percentages are within reasonable bounds (0-100%)
+        require!(
+            self.buy_fee_percentage <= FEE_PRECISION && 
+            self.sell_fee_percentage <= FEE_PRECISION && 
+            self.migration_fee_percentage <= FEE_PRECISION,
+            SwifeyError::InvalidFeePercentage
+        );
+
+        // Creator fee is charged on top of the buy/sell fee, so the sum must stay within 100%
+        require!(
+            self.buy_fee_percentage.saturating_add(self.creator_fee_percentage) <= FEE_PRECISION &&
+            self.sell_fee_percentage.saturating_add(self.creator_fee_percentage) <= FEE_PRECISION,
+            SwifeyError::InvalidFeePercentage
+        );
+
+        // 2. Validate initial virtual token reserve is at least 80% of total supply
+        let min_virtual_token_reserve = self.total_token_supply
+            .checked_mul(80)
+            .ok_or(SwifeyError::MathOverflow)?
+            / 100;
+        require!(
+            self.initial_virtual_token_reserve >= min_virtual_token_reserve,
+            SwifeyError::InvalidTokenAllocation
+        );
+
+        // 3. Validate initial SOL reserve is non-zero and reasonable
+        require!(
+            self.initial_virtual_sol_reserve >= LAMPORTS_PER_SOL, // At least 1 SOL
+            SwifeyError::InsufficientLiquidity
+        );
+
+        // 4. Validate curve limit is greater than initial SOL reserve
+        require!(
+            self.curve_limit > self.initial_virtual_sol_reserve,
+            SwifeyError::InvalidCurveLimit
+        );
+
+        // 5. Validate total token supply is non-zero
+        require!(
+            self.total_token_supply > 0,
+            SwifeyError::InvalidTokenAllocation
+        );
+
+        Ok(())
+    }
+}
+
+// Launch tier selectable at launch, managed by the config authority
+#[account]
+pub struct CurveTemplate {
+    pub index: u16,
+    pub params: CurveParams,
+    pub is_active: bool, // Inactive templates can no longer be used for launches
+    pub bump: u8,
+    pub reserved: [u8; 8]
+}
+
+impl CurveTemplate {
+    pub const SEED_PREFIX: &'static str = "curve_template";
+    pub const LEN: usize = 2 + // index
+        CurveParams::LEN + // params
+        1 + // is_active
+        1 + // bump
+        8; // reserved
+}
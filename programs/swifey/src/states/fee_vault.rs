@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::SwifeyError;
+use crate::utils::FeesAccrued;
+
+// Program-owned PDA that holds platform fees until the authority claims them
+#[account]
+pub struct FeeVault {
+    pub total_accrued: u64, // All platform fees ever received, in lamports
+    pub total_claimed: u64, // All platform fees ever paid out, in lamports
+    pub bump: u8,
+    pub reserved: [u8; 8]
+}
+
+impl FeeVault {
+    pub const SEED_PREFIX: &'static str = "fee_vault";
+    pub const LEN: usize = 8 + // total_accrued
+        8 + // total_claimed
+        1 + // bump
+        8; // reserved
+
+    // Record fees that were just moved into the vault
+    pub fn accrue(&mut self, token_mint: Pubkey, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        self.total_accrued = self.total_accrued
+            .checked_add(amount)
+            .ok_or(SwifeyError::MathOverflow)?;
+
+        emit!(FeesAccrued {
+            token_mint,
+            amount,
+            total_accrued: self.total_accrued,
+        });
+        Ok(())
+    }
+
+    // Fees held by the vault that have not been claimed yet
+    pub fn unclaimed(&self) -> Result<u64> {
+        self.total_accrued
+            .checked_sub(self.total_claimed)
+            .ok_or(error!(SwifeyError::MathOverflow))
+    }
+}
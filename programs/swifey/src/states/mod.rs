@@ -3,3 +3,15 @@ pub use config::*;
 
 pub mod bonding_curve;
 pub use bonding_curve::*;
+
+pub mod curve_template;
+pub use curve_template::*;
+
+pub mod fee_vault;
+pub use fee_vault::*;
+
+pub mod pending_config;
+pub use pending_config::*;
+
+pub mod multisig;
+pub use multisig::*;
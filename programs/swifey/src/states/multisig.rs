@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+use crate::errors::SwifeyError;
+
+// M-of-N approver set for admin actions. Its signer PDA holds the config authority, so admin
+// instructions only run through proposals that reached the threshold
+#[account]
+pub struct Multisig {
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8, // Approvals needed to execute a proposal
+    pub version: u32, // Bumped on every approver change, proposals from older versions go stale
+    pub proposal_count: u64, // Index of the next proposal
+    pub bump: u8,
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub const SEED_PREFIX: &'static str = "multisig";
+    pub const MAX_APPROVERS: usize = 10;
+    pub const LEN: usize = 4 + 32 * Self::MAX_APPROVERS + // approvers
+        1 + // threshold
+        4 + // version
+        8 + // proposal_count
+        1 + // bump
+        1; // signer_bump
+
+    pub fn get_signer<'a>(bump: &'a u8) -> [&'a [u8]; 2] {
+        [
+            crate::constants::MULTISIG_SIGNER_SEED.as_bytes(),
+            std::slice::from_ref(bump),
+        ]
+    }
+
+    pub fn validate_approvers(approvers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !approvers.is_empty() && approvers.len() <= Self::MAX_APPROVERS,
+            SwifeyError::InvalidApprovers
+        );
+        require!(
+            threshold > 0 && threshold as usize <= approvers.len(),
+            SwifeyError::InvalidThreshold
+        );
+
+        for (i, approver) in approvers.iter().enumerate() {
+            require!(
+                *approver != Pubkey::default() && !approvers[..i].contains(approver),
+                SwifeyError::InvalidApprovers
+            );
+        }
+        Ok(())
+    }
+
+    // Replace the approver set, which invalidates every open proposal
+    pub fn set_approvers(&mut self, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        Self::validate_approvers(&approvers, threshold)?;
+
+        self.approvers = approvers;
+        self.threshold = threshold;
+        self.version = self.version
+            .checked_add(1)
+            .ok_or(SwifeyError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn approver_index(&self, key: &Pubkey) -> Result<usize> {
+        self.approvers
+            .iter()
+            .position(|approver| approver == key)
+            .ok_or(error!(SwifeyError::NotAnApprover))
+    }
+}
+
+// Account of a proposed instruction, in the order the instruction expects them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Admin instruction of this program waiting for approvals, executed by the multisig signer
+#[account]
+pub struct AdminProposal {
+    pub multisig_version: u32, // Approver set the proposal was created under
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub accounts: Vec<ProposalAccount>,
+    pub data: Vec<u8>, // Instruction data, including the discriminator
+    pub approvals: u16, // Bit i is set once approver i approved
+    pub is_executed: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl AdminProposal {
+    pub const SEED_PREFIX: &'static str = "admin_proposal";
+    pub const MAX_ACCOUNTS: usize = 8;
+    pub const MAX_DATA_LEN: usize = 512;
+    pub const LEN: usize = 4 + // multisig_version
+        8 + // index
+        32 + // proposer
+        4 + 34 * Self::MAX_ACCOUNTS + // accounts
+        4 + Self::MAX_DATA_LEN + // data
+        2 + // approvals
+        1 + // is_executed
+        8 + // created_at
+        1; // bump
+
+    pub fn approve(&mut self, approver_index: usize) -> Result<()> {
+        let bit = 1u16 << approver_index;
+        require!(self.approvals & bit == 0, SwifeyError::AlreadyApproved);
+
+        self.approvals |= bit;
+        Ok(())
+    }
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+
+    // Rebuild the proposed instruction, the multisig signer signs in place of a key
+    pub fn instruction(&self, signer: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: self.accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer || account.pubkey == *signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_approvers() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(Multisig::validate_approvers(&[a, b], 2).is_ok());
+        assert!(Multisig::validate_approvers(&[a, b], 0).is_err());
+        assert!(Multisig::validate_approvers(&[a, b], 3).is_err());
+        assert!(Multisig::validate_approvers(&[a, a], 1).is_err());
+        assert!(Multisig::validate_approvers(&[a, Pubkey::default()], 1).is_err());
+        assert!(Multisig::validate_approvers(&[], 0).is_err());
+        assert!(Multisig::validate_approvers(&[Pubkey::new_unique(); 11], 1).is_err());
+    }
+
+    #[test]
+    fn test_proposal_approvals() {
+        let mut proposal = AdminProposal {
+            multisig_version: 1,
+            index: 0,
+            proposer: Pubkey::default(),
+            accounts: vec![],
+            data: vec![],
+            approvals: 0,
+            is_executed: false,
+            created_at: 0,
+            bump: 0,
+        };
+
+        proposal.approve(0).unwrap();
+        proposal.approve(3).unwrap();
+        assert!(proposal.approve(3).is_err());
+        assert_eq!(proposal.approval_count(), 2);
+    }
+}
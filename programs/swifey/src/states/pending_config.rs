@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::SwifeyError;
+use crate::states::{Config, ConfigSettings, CurveSettings, FeeSettings};
+use crate::utils::ConfigChangeQueued;
+
+// Settings a queued change replaces. Partial changes are merged into the config as it is at
+// execution, so instant updates made in the meantime (e.g. set_fee_recipient) are kept
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ConfigChange {
+    Full(ConfigSettings), // configure
+    Fees(FeeSettings),    // set_fees
+    Curve(CurveSettings), // set_curve_params
+}
+
+impl ConfigChange {
+    // Settings of the config once the change is applied on top of `current`
+    pub fn apply(&self, current: ConfigSettings) -> ConfigSettings {
+        match self {
+            ConfigChange::Full(settings) => settings.clone(),
+            ConfigChange::Fees(fees) => ConfigSettings {
+                buy_fee_percentage: fees.buy_fee_percentage,
+                sell_fee_percentage: fees.sell_fee_percentage,
+                migration_fee_percentage: fees.migration_fee_percentage,
+                creator_fee_percentage: fees.creator_fee_percentage,
+                keeper_reward_percentage: fees.keeper_reward_percentage,
+                ..current
+            },
+            ConfigChange::Curve(curve) => ConfigSettings {
+                curve_limit: curve.curve_limit,
+                initial_virtual_token_reserve: curve.initial_virtual_token_reserve,
+                initial_virtual_sol_reserve: curve.initial_virtual_sol_reserve,
+                total_token_supply: curve.total_token_supply,
+                ..current
+            },
+        }
+    }
+}
+
+// Config change waiting for its timelock, applied by execute_config or dropped by cancel_config
+#[account]
+pub struct PendingConfig {
+    pub change: ConfigChange,
+    pub proposer: Pubkey, // Paid the rent, refunded when the change is executed or cancelled
+    pub queued_at: i64,
+    pub execute_after: i64, // Earliest unix timestamp execute_config accepts
+    pub bump: u8,
+}
+
+impl PendingConfig {
+    pub const SEED_PREFIX: &'static str = "pending_config";
+    pub const LEN: usize = 1 + ConfigSettings::LEN + // change, sized for its largest variant
+        32 + // proposer
+        8 + // queued_at
+        8 + // execute_after
+        1; // bump
+
+    // Queue a change behind the timelock of the current config, once it validates against it
+    pub fn queue(&mut self, config: &Config, change: ConfigChange, proposer: Pubkey, bump: u8) -> Result<()> {
+        let settings = change.apply(config.settings());
+        settings.validate()?;
+
+        // The authority only changes through propose_authority and accept_authority
+        require!(
+            settings.authority == config.authority,
+            SwifeyError::AuthorityChangeNotAllowed
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        self.change = change;
+        self.proposer = proposer;
+        self.queued_at = now;
+        self.execute_after = now
+            .checked_add(config.config_timelock)
+            .ok_or(SwifeyError::MathOverflow)?;
+        self.bump = bump;
+
+        emit!(ConfigChangeQueued {
+            proposer,
+            execute_after: self.execute_after,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_change_keeps_other_settings() {
+        let queued = ConfigChange::Fees(FeeSettings {
+            buy_fee_percentage: 200,
+            sell_fee_percentage: 300,
+            migration_fee_percentage: 400,
+            creator_fee_percentage: 50,
+            keeper_reward_percentage: 1_000,
+        });
+
+        // The fee recipient changed instantly while the fees were queued
+        let current = ConfigSettings {
+            fee_recipient: Pubkey::new_unique(),
+            curve_limit: 80_000_000_000,
+            buy_fee_percentage: 100,
+            ..ConfigSettings::default()
+        };
+
+        let applied = queued.apply(current.clone());
+        assert_eq!(applied.fee_recipient, current.fee_recipient);
+        assert_eq!(applied.curve_limit, current.curve_limit);
+        assert_eq!(applied.buy_fee_percentage, 200);
+        assert_eq!(applied.keeper_reward_percentage, 1_000);
+    }
+}
@@ -1,12 +1,20 @@
 use anchor_lang::prelude::*;
 
+use crate::states::{CurveStatus, LpPolicy, MigrationTarget, PauseFlags, Role};
+
 #[event]
 pub struct MigrationCompleted {
     pub token_mint: Pubkey,
     pub sol_amount: u64,
     pub token_amount: u64,
     pub migration_fee: u64,
+    pub keeper: Pubkey,
+    pub keeper_reward: u64,
+    pub migration_target: MigrationTarget,
     pub raydium_pool: Pubkey,
+    pub lp_mint: Pubkey, // Position NFT mint for CLMM, LP token mint for CPMM
+    pub liquidity: u128, // Position liquidity for CLMM, LP tokens received for CPMM
+    pub lp_policy: LpPolicy, // Burned or locked, the position can no longer be withdrawn
 }
 
 #[event]
@@ -16,9 +24,14 @@ pub struct TokenSold {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub fee_amount: u64,
+    pub creator: Pubkey,
+    pub creator_fee_amount: u64,
     pub price: u64,
     pub new_sol_reserves: u64,  
     pub new_token_reserves: u64,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub price_impact_bps: u64,
 }
 
 #[event]
@@ -28,9 +41,15 @@ pub struct TokenPurchased {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub fee_amount: u64,
+    pub creator: Pubkey,
+    pub creator_fee_amount: u64,
     pub price: u64,
     pub new_sol_reserves: u64,  
     pub new_token_reserves: u64,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub price_impact_bps: u64,
+    pub refund_amount: u64, // SOL and fees not charged when the buy only partially filled
 }
 
 #[event]
@@ -48,12 +67,17 @@ pub struct ConfigurationInitialized {
     pub curve_limit: u64,
     pub initial_virtual_token_reserve: u64,
     pub initial_virtual_sol_reserve: u64,
-    pub initial_real_token_reserve: u64,
     pub total_token_supply: u64,
     pub buy_fee_percentage: u64,
     pub sell_fee_percentage: u64,
     pub migration_fee_percentage: u64,
-    pub is_paused: bool,
+    pub creator_fee_percentage: u64,
+    pub keeper_reward_percentage: u64,
+    pub lp_policy: LpPolicy,
+    pub migration_target: MigrationTarget,
+    pub clmm_amm_config: Pubkey,
+    pub cpmm_amm_config: Pubkey,
+    pub pause_flags: PauseFlags,
     pub timestamp: i64,
 }
 
@@ -72,8 +96,110 @@ pub struct ConfigurationUpdated {
     pub new_sell_fee_percentage: u64,
     pub old_migration_fee_percentage: u64,
     pub new_migration_fee_percentage: u64,
-    pub old_is_paused: bool,
-    pub new_is_paused: bool,
+    pub old_creator_fee_percentage: u64,
+    pub new_creator_fee_percentage: u64,
+    pub old_keeper_reward_percentage: u64,
+    pub new_keeper_reward_percentage: u64,
+    pub old_lp_policy: LpPolicy,
+    pub new_lp_policy: LpPolicy,
+    pub old_migration_target: MigrationTarget,
+    pub new_migration_target: MigrationTarget,
+    pub old_clmm_amm_config: Pubkey,
+    pub new_clmm_amm_config: Pubkey,
+    pub old_cpmm_amm_config: Pubkey,
+    pub new_cpmm_amm_config: Pubkey,
+    pub old_pause_flags: PauseFlags,
+    pub new_pause_flags: PauseFlags,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub proposer: Pubkey,
+    pub execute_after: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub authority: Pubkey,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub authority: Pubkey,
+    pub old_pause_flags: PauseFlags,
+    pub new_pause_flags: PauseFlags,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesUpdated {
+    pub authority: Pubkey,
+    pub old_buy_fee_percentage: u64,
+    pub new_buy_fee_percentage: u64,
+    pub old_sell_fee_percentage: u64,
+    pub new_sell_fee_percentage: u64,
+    pub old_migration_fee_percentage: u64,
+    pub new_migration_fee_percentage: u64,
+    pub old_creator_fee_percentage: u64,
+    pub new_creator_fee_percentage: u64,
+    pub old_keeper_reward_percentage: u64,
+    pub new_keeper_reward_percentage: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeRecipientUpdated {
+    pub authority: Pubkey,
+    pub old_fee_recipient: Pubkey,
+    pub new_fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveParamsUpdated {
+    pub authority: Pubkey,
+    pub old_curve_limit: u64,
+    pub new_curve_limit: u64,
+    pub old_initial_virtual_token_reserve: u64,
+    pub new_initial_virtual_token_reserve: u64,
+    pub old_initial_virtual_sol_reserve: u64,
+    pub new_initial_virtual_sol_reserve: u64,
+    pub old_total_token_supply: u64,
+    pub new_total_token_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub authority: Pubkey,
+    pub role: Role,
+    pub old_account: Pubkey,
+    pub new_account: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposalCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
     pub timestamp: i64,
 }
 
@@ -85,7 +211,126 @@ pub struct TokenLaunched {
     pub initial_virtual_token_reserve: u64,
     pub total_token_supply: u64,
     pub curve_limit: u64,
+    pub buy_fee_percentage: u64,
+    pub sell_fee_percentage: u64,
+    pub migration_fee_percentage: u64,
+    pub creator_fee_percentage: u64,
+    pub template_index: Option<u16>,
+    pub migration_target: MigrationTarget,
     pub name: String,
     pub symbol: String,
     pub uri: String,
 }
+
+#[event]
+pub struct CurveTemplateUpdated {
+    pub authority: Pubkey,
+    pub curve_template: Pubkey,
+    pub index: u16,
+    pub curve_limit: u64,
+    pub initial_virtual_token_reserve: u64,
+    pub initial_virtual_sol_reserve: u64,
+    pub total_token_supply: u64,
+    pub buy_fee_percentage: u64,
+    pub sell_fee_percentage: u64,
+    pub migration_fee_percentage: u64,
+    pub creator_fee_percentage: u64,
+    pub is_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub token_mint: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesAccrued {
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64,
+}
+
+#[event]
+pub struct FeesClaimed {
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub amount: u64,
+    pub total_accrued: u64,
+    pub total_claimed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MultisigUpdated {
+    pub multisig: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub version: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub proposal: Pubkey,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal: Pubkey,
+    pub executor: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveStatusUpdated {
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub old_status: CurveStatus,
+    pub new_status: CurveStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveUnwindStarted {
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub unwind_at: i64,
+    pub real_sol_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensRedeemed {
+    pub token_mint: Pubkey,
+    pub holder: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveUpgraded {
+    pub token_mint: Pubkey,
+    pub payer: Pubkey,
+    pub real_sol_reserve: u64,
+    pub real_token_reserve: u64,
+    pub timestamp: i64,
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{errors::SwifeyError, constants::{PRECISION, PRECISION_U64}};
+use crate::{errors::SwifeyError, constants::{FEE_PRECISION, PRECISION, PRECISION_U64}};
 
 /// Fixed-point multiplication for u128
 pub fn fixed_mul_u128(a: u64, b: u128) -> Result<u64> {
@@ -76,240 +76,198 @@ pub fn fixed_div(a: u64, b: u64) -> Result<u64> {
     Ok(result as u64)
 }
 
-/// Fixed-point power function using binary exponentiation and scaling
-pub fn fixed_pow(base: u64, exp: u64) -> Result<u64> {
-    msg!("fixed_pow: Starting with base={}, exp={}", base, exp);
-    
-    // Validate inputs
-    if base == 0 {
-        msg!("fixed_pow: Base is zero, returning DivisionByZero error");
+// ln(2) scaled by 10^18, used for range reduction in `fixed_ln_u128`/`fixed_exp_u128`
+const LN_2_E18: i128 = 693_147_180_559_945_309;
+const LN_2_SCALE: i128 = 1_000_000; // 10^18 / PRECISION
+
+// sqrt(2) and 1/sqrt(2) scaled by PRECISION, bounds of the reduced ln argument
+const SQRT_2: u128 = 1_414_213_562_373;
+const INV_SQRT_2: u128 = 707_106_781_186;
+
+// Ratio between the u128 (PRECISION) and u64 (PRECISION_U64) fixed-point scales
+const U64_SCALE: u128 = PRECISION / PRECISION_U64 as u128;
+
+/// Natural logarithm of a PRECISION-scaled value, returned as a signed PRECISION-scaled value.
+///
+/// `x` is reduced to `y * 2^k` with `y` in `[1/sqrt(2), sqrt(2))` and `ln(y)` is evaluated
+/// with the `2 * atanh((y - 1) / (y + 1))` series, which converges in under 10 terms.
+pub fn fixed_ln_u128(x: u128) -> Result<i128> {
+    if x == 0 {
         return Err(error!(SwifeyError::DivisionByZero));
     }
-    
-    // Normalization for large bases instead of saturation
-    const MAX_SAFE_BASE: u64 = 10 * PRECISION_U64; // bases above this are considered too high
-    if base > MAX_SAFE_BASE {
-        msg!("fixed_pow: Base {} exceeds MAX_SAFE_BASE {}. Normalizing.", base, MAX_SAFE_BASE);
-        let mut norm_count = 0;
-        let mut normalized_base = base;
-        while normalized_base > MAX_SAFE_BASE {
-            normalized_base = fixed_div(normalized_base, 10)?;
-            norm_count += 1;
-        }
-        msg!("fixed_pow: Normalized base = {} after {} divisions", normalized_base, norm_count);
-        let safe_result = fixed_pow(normalized_base, exp)?;
-        // Compute correction exponent: (norm_count * (exp as real))
-        let exp_multiplier = (norm_count as u64)
-            .checked_mul(exp).ok_or_else(|| error!(SwifeyError::MathOverflow))?
-            .checked_div(PRECISION_U64).ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-        msg!("fixed_pow: Correction exponent = {}", exp_multiplier);
-        // In fixed point, 10 is represented as 10 * PRECISION_U64
-        let correction = fixed_pow(10 * PRECISION_U64, exp_multiplier)?;
-        msg!("fixed_pow: Correction factor = {}", correction);
-        let result = fixed_mul(safe_result, correction)?;
-        msg!("fixed_pow: Final result after normalization = {}", result);
-        return Ok(result);
-    }
-    
-    // For exp = 0, return PRECISION (1.0 in fixed point)
-    if exp == 0 {
-        msg!("fixed_pow: Exponent is 0, returning PRECISION={}", PRECISION_U64);
-        return Ok(PRECISION_U64);
-    }
-    
-    // For exp = PRECISION (1.0), return base unchanged
-    if exp == PRECISION_U64 {
-        msg!("fixed_pow: Exponent is PRECISION, returning base={}", base);
-        return Ok(base);
+
+    if x == PRECISION {
+        return Ok(0);
     }
-    
-    // For base = PRECISION (1.0), return PRECISION
-    if base == PRECISION_U64 {
-        msg!("fixed_pow: Base is PRECISION, returning PRECISION={}", PRECISION_U64);
-        return Ok(PRECISION_U64);
+
+    // Range reduction by powers of two (shifts compose exactly, so this floors only once)
+    let mut y = x;
+    let mut k: i128 = 0;
+    while y >= SQRT_2 {
+        y >>= 1;
+        k += 1;
     }
-    
-    // Handle fractional exponents
-    if exp < PRECISION_U64 {
-        msg!("fixed_pow: Handling fractional exponent (exp < PRECISION)");
-        
-        // First normalize the base to be close to PRECISION
-        let base_normalized = if base > PRECISION_U64 {
-            msg!("fixed_pow: Normalizing large base {} by dividing with PRECISION", base);
-            let normalized = fixed_div(base, PRECISION_U64)?;
-            msg!("fixed_pow: Normalized base = {}", normalized);
-            normalized
-        } else {
-            msg!("fixed_pow: Base {} is already normalized", base);
-            base
-        };
-
-        // Calculate ln(base)
-        msg!("fixed_pow: Calculating ln(base_normalized={})", base_normalized);
-        let ln_base = fixed_ln(base_normalized)?;
-        msg!("fixed_pow: ln(base) = {}", ln_base);
-        
-        // Calculate exp * ln(base)
-        msg!("fixed_pow: Calculating exp({}) * ln_base({})", exp, ln_base);
-        let exp_mul = fixed_mul(ln_base, exp)?;
-        msg!("fixed_pow: exp * ln(base) = {}", exp_mul);
-        
-        // Calculate e^(exp * ln(base))
-        msg!("fixed_pow: Calculating e^({})", exp_mul);
-        let result = fixed_exp(exp_mul)?;
-        msg!("fixed_pow: e^(exp * ln(base)) = {}", result);
-
-        // If we normalized the base, we need to adjust the result
-        if base > PRECISION_U64 {
-            msg!("fixed_pow: Adjusting result for normalized base");
-            let scale_factor = fixed_div(base, PRECISION_U64)?;
-            msg!("fixed_pow: Scale factor = {}", scale_factor);
-            let scale_exp = fixed_mul(exp, PRECISION_U64)?;
-            msg!("fixed_pow: Scale exponent = {}", scale_exp);
-            let mut final_result = result;
-            
-            // Apply scaling factor raised to the exponent
-            let scale_iterations = scale_exp.checked_div(PRECISION_U64)
-                .ok_or_else(|| error!(SwifeyError::DivisionByZero))?;
-            msg!("fixed_pow: Applying scale factor {} for {} iterations", scale_factor, scale_iterations);
-            
-            for i in 0..scale_iterations {
-                final_result = fixed_mul(final_result, scale_factor)?;
-                msg!("fixed_pow: After iteration {}: final_result = {}", i + 1, final_result);
-            }
-            msg!("fixed_pow: Final adjusted result = {}", final_result);
-            return Ok(final_result);
-        }
-        msg!("fixed_pow: Returning unadjusted result = {}", result);
-        return Ok(result);
+    while y < INV_SQRT_2 {
+        y <<= 1;
+        k -= 1;
     }
 
-    // Handle integer exponent using binary exponentiation
-    msg!("fixed_pow: Handling integer exponent");
-    let mut result = PRECISION_U64;
-    let mut current_exp = exp.checked_div(PRECISION_U64)
-        .ok_or_else(|| error!(SwifeyError::DivisionByZero))?;
-    let mut current_base = base;
-
-    // Use efficient binary exponentiation (exponentiation by squaring)
-    while current_exp > 0 {
-        msg!("fixed_pow: Current exp={}, result={}, base={}", current_exp, result, current_base);
-        
-        if current_exp & 1 == 1 {
-            result = fixed_mul(result, current_base)?;
-            msg!("fixed_pow: After odd exp multiplication: result={}", result);
-        }
-        
-        if current_exp > 1 {  // Only square if we have more iterations to go
-            current_base = fixed_mul(current_base, current_base)?;
-            msg!("fixed_pow: After squaring: current_base={}", current_base);
-        }
-        
-        current_exp = current_exp.checked_shr(1)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+    let precision = PRECISION as i128;
+    let y = y as i128;
+    let z = (y - precision)
+        .checked_mul(precision)
+        .ok_or_else(|| error!(SwifeyError::MathOverflow))?
+        / (y + precision);
+    let z_squared = z * z / precision;
+
+    // atanh(z) = z + z^3/3 + z^5/5 + ...
+    let mut sum: i128 = 0;
+    let mut term = z;
+    let mut n: i128 = 1;
+    while term != 0 {
+        sum += term / n;
+        term = term * z_squared / precision;
+        n += 2;
     }
 
-    msg!("fixed_pow: Final result = {}", result);
-    Ok(result)
+    let shift_component = k
+        .checked_mul(LN_2_E18)
+        .ok_or_else(|| error!(SwifeyError::MathOverflow))?
+        / LN_2_SCALE;
+
+    Ok(2 * sum + shift_component)
 }
 
-/// Natural logarithm for fixed-point numbers using Taylor series
-pub fn fixed_ln(x: u64) -> Result<u64> {
+/// Exponential of a signed PRECISION-scaled value, returned as a PRECISION-scaled value.
+///
+/// `x` is split into `k * ln(2) + r` with `r` in `[0, ln(2))`, `e^r` is evaluated with its
+/// Taylor series and the result is shifted by `2^k`. Results below one unit round to zero.
+pub fn fixed_exp_u128(x: i128) -> Result<u128> {
     if x == 0 {
-        return Err(error!(SwifeyError::DivisionByZero));
+        return Ok(PRECISION);
     }
-    
-    // If x is very close to PRECISION (1.0), return 0
-    if x == PRECISION_U64 {
-        return Ok(0);
+
+    let scaled_x = x
+        .checked_mul(LN_2_SCALE)
+        .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+    let k = scaled_x.div_euclid(LN_2_E18);
+    let r = (scaled_x - k * LN_2_E18) / LN_2_SCALE;
+
+    // e^r = 1 + r + r^2/2! + ...
+    let precision = PRECISION as i128;
+    let mut sum = precision;
+    let mut term = precision;
+    let mut n: i128 = 1;
+    while term != 0 {
+        term = term * r / (n * precision);
+        sum += term;
+        n += 1;
     }
+    let sum = sum as u128;
 
-    // For x < 1, use ln(x) = -ln(1/x)
-    if x < PRECISION_U64 {
-        let inverse = fixed_div(PRECISION_U64, x)?;
-        let ln_inverse = fixed_ln(inverse)?;
-        return Ok(PRECISION_U64.checked_sub(ln_inverse)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?);
+    if k >= 0 {
+        let shift = u32::try_from(k).map_err(|_| error!(SwifeyError::MathOverflow))?;
+        if shift >= sum.leading_zeros() {
+            return Err(error!(SwifeyError::MathOverflow));
+        }
+        Ok(sum << shift)
+    } else if -k >= 128 {
+        Ok(0)
+    } else {
+        Ok(sum >> (-k) as u32)
     }
+}
 
-    // Normalize x to be close to 1 for better convergence
-    let mut shift = 0u64;
-    let mut normalized_x = x;
-    while normalized_x >= PRECISION_U64.checked_mul(2)
-        .ok_or_else(|| error!(SwifeyError::MathOverflow))? {
-        normalized_x = fixed_div(normalized_x, 2 * PRECISION_U64)?;
-        shift = shift.checked_add(1)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+/// Fixed-point power function for PRECISION-scaled base and exponent, computed as
+/// `e^(exp * ln(base))`. The relative error stays around 10^-11 over the curve's ratio range.
+pub fn fixed_pow_u128(base: u128, exp: u128) -> Result<u128> {
+    if base == 0 {
+        return Err(error!(SwifeyError::DivisionByZero));
     }
 
-    // Use Taylor series for ln(1 + y) where y = x - 1
-    let y = normalized_x.checked_sub(PRECISION_U64)
-        .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-    let mut sum = 0u64;
-    let mut term = y;
-    let mut n = 1u64;
-    
-    while term > 0 && n < 10 {
-        if n % 2 == 1 {
-            sum = sum.checked_add(fixed_div(term, n * PRECISION_U64)?)
-                .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-        } else {
-            sum = sum.checked_sub(fixed_div(term, n * PRECISION_U64)?)
-                .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-        }
-        term = fixed_mul(term, y)?;
-        n = n.checked_add(1)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+    if exp == 0 || base == PRECISION {
+        return Ok(PRECISION);
     }
 
-    // Add back the shift component: ln(x) = ln(x/2^k) + k*ln(2)
-    if shift > 0 {
-        let ln2 = 693147180559945309; // ln(2) * PRECISION
-        let shift_component = shift.checked_mul(ln2)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-        sum = sum.checked_add(shift_component)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+    if exp == PRECISION {
+        return Ok(base);
     }
-    
-    Ok(sum)
+
+    let ln_base = fixed_ln_u128(base)?;
+    let exp = i128::try_from(exp).map_err(|_| error!(SwifeyError::MathOverflow))?;
+    let exponent = ln_base
+        .checked_mul(exp)
+        .ok_or_else(|| error!(SwifeyError::MathOverflow))?
+        / PRECISION as i128;
+
+    fixed_exp_u128(exponent)
 }
 
-/// Exponential function for fixed-point numbers using Taylor series
-pub fn fixed_exp(x: u64) -> Result<u64> {
-    // If x is 0, return PRECISION (1.0)
-    if x == 0 {
-        return Ok(PRECISION_U64);
+/// Fixed-point power function for PRECISION_U64-scaled values
+pub fn fixed_pow(base: u64, exp: u64) -> Result<u64> {
+    let result = fixed_pow_u128(base as u128 * U64_SCALE, exp as u128 * U64_SCALE)? / U64_SCALE;
+    u64::try_from(result).map_err(|_| error!(SwifeyError::MathOverflow))
+}
+
+/// Natural logarithm for PRECISION_U64-scaled values
+pub fn fixed_ln(x: u64) -> Result<i64> {
+    let result = fixed_ln_u128(x as u128 * U64_SCALE)? / U64_SCALE as i128;
+    i64::try_from(result).map_err(|_| error!(SwifeyError::MathOverflow))
+}
+
+/// Exponential function for PRECISION_U64-scaled values
+pub fn fixed_exp(x: i64) -> Result<u64> {
+    let result = fixed_exp_u128(x as i128 * U64_SCALE as i128)? / U64_SCALE;
+    u64::try_from(result).map_err(|_| error!(SwifeyError::MathOverflow))
+}
+
+/// Integer square root, rounded down
+pub fn sqrt_u128(x: u128) -> u128 {
+    if x < 2 {
+        return x;
     }
 
-    // Limit input range to prevent overflow
-    if x > 41446531673968666 { // ln(2^64) * PRECISION
-        return Err(error!(SwifeyError::MathOverflow));
+    // Newton's method from a power of two above the root, which decreases monotonically
+    let mut root = 1u128 << ((128 - x.leading_zeros() + 1) / 2);
+    loop {
+        let next = (root + x / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
     }
+}
 
-    let mut sum = PRECISION_U64;
-    let mut term = PRECISION_U64;
-    let mut n = 1u64;
-    
-    while term > 0 && n < 15 {
-        term = fixed_mul(term, fixed_div(x, n * PRECISION_U64)?)?;
-        sum = sum.checked_add(term)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
-        n = n.checked_add(1)
-            .ok_or_else(|| error!(SwifeyError::MathOverflow))?;
+/// Square root of amount_1 / amount_0 as a Q64.64 value, the sqrt price format of Raydium CLMM
+pub fn sqrt_price_x64(amount_0: u64, amount_1: u64) -> Result<u128> {
+    if amount_0 == 0 {
+        return Err(error!(SwifeyError::DivisionByZero));
     }
-    
-    Ok(sum)
+
+    // sqrt(amount_1 / amount_0) << 64 = sqrt((amount_1 << shift) / amount_0) << (64 - shift / 2),
+    // with an even shift as large as u128 allows to keep precision
+    let shift = ((amount_1 as u128).leading_zeros() & !1).min(126);
+    let ratio = ((amount_1 as u128) << shift) / amount_0 as u128;
+    Ok(sqrt_u128(ratio) << (64 - shift / 2))
 }
 
-/// Calculate fee amount using fixed-point arithmetic
+/// Calculate fee amount for a percentage in basis points (FEE_PRECISION = 100%), rounded down
 pub fn calculate_fee_amount(amount: u64, fee_percentage: u64) -> Result<u64> {
-    fixed_mul(amount, fee_percentage)
+    let fee_amount = (amount as u128)
+        .checked_mul(fee_percentage as u128)
+        .ok_or_else(|| error!(SwifeyError::MathOverflow))?
+        .checked_div(FEE_PRECISION as u128)
+        .ok_or_else(|| error!(SwifeyError::DivisionByZero))?;
+
+    u64::try_from(fee_amount).map_err(|_| error!(SwifeyError::MathOverflow))
 }
 
 // Unit tests for fixed_pow
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::{CRR_NUMERATOR, CRR_DENOMINATOR};
+    use crate::utils::test_utils::{assert_close, next_random};
 
     #[test]
     fn test_fixed_pow_integer_exponent() {
@@ -340,4 +298,107 @@ mod tests {
          // Expected result should be approximately base. Allow for some rounding error.
          assert!((result as i64 - (1000 * PRECISION_U64) as i64).abs() < 1000);
     }
-} 
\ No newline at end of file
+
+    // Deterministic xorshift generator so the property tests are reproducible
+    #[test]
+    fn test_fixed_ln_matches_f64() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            // Log-uniform over [1e-9, 1e9]
+            let exponent = (next_random(&mut seed) % 18_000) as f64 / 1000.0 - 9.0;
+            let x = 10f64.powf(exponent);
+            let x_fixed = (x * PRECISION as f64) as u128;
+            let result = fixed_ln_u128(x_fixed).unwrap() as f64 / PRECISION as f64;
+            let expected = (x_fixed as f64 / PRECISION as f64).ln();
+            assert_close(result, expected, 1e-10, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_fixed_exp_matches_f64() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..2000 {
+            // Uniform over [-40, 40]
+            let x = (next_random(&mut seed) % 80_000) as f64 / 1000.0 - 40.0;
+            let x_fixed = (x * PRECISION as f64) as i128;
+            let result = fixed_exp_u128(x_fixed).unwrap() as f64 / PRECISION as f64;
+            let expected = (x_fixed as f64 / PRECISION as f64).exp();
+            assert_close(result, expected, 1e-10, 1e-11);
+        }
+    }
+
+    #[test]
+    fn test_fixed_pow_crr_matches_f64() {
+        let crr = CRR_NUMERATOR as f64 / CRR_DENOMINATOR as f64;
+        let crr_fixed = fixed_div_u128(CRR_NUMERATOR, CRR_DENOMINATOR).unwrap();
+        let inverse_crr_fixed = fixed_div_u128(CRR_DENOMINATOR, CRR_NUMERATOR).unwrap();
+
+        let mut seed = 0xdead_beef_cafe_f00d;
+        for _ in 0..2000 {
+            // Ratios in (0, 1] as produced by current_reserve / new_reserve
+            let exponent = (next_random(&mut seed) % 9_000) as f64 / 1000.0 - 9.0;
+            let ratio_fixed = ((10f64.powf(exponent) * PRECISION as f64) as u128).max(1);
+            let ratio = ratio_fixed as f64 / PRECISION as f64;
+
+            let result = fixed_pow_u128(ratio_fixed, crr_fixed).unwrap() as f64 / PRECISION as f64;
+            assert_close(result, ratio.powf(crr), 1e-9, 1e-11);
+
+            let result = fixed_pow_u128(ratio_fixed, inverse_crr_fixed).unwrap() as f64 / PRECISION as f64;
+            assert_close(result, ratio.powf(1.0 / crr), 1e-9, 1e-11);
+        }
+    }
+
+    #[test]
+    fn test_fixed_exp_overflow() {
+        assert!(fixed_exp_u128(100 * PRECISION as i128).is_err());
+        assert_eq!(fixed_exp_u128(-100 * PRECISION as i128).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sqrt_u128() {
+        assert_eq!(sqrt_u128(0), 0);
+        assert_eq!(sqrt_u128(1), 1);
+        assert_eq!(sqrt_u128(15), 3);
+        assert_eq!(sqrt_u128(16), 4);
+        assert_eq!(sqrt_u128(u128::MAX), u64::MAX as u128);
+
+        let mut seed = 0x1357_9bdf_2468_ace0;
+        for _ in 0..2000 {
+            let x = ((next_random(&mut seed) as u128) << 64) | next_random(&mut seed) as u128;
+            let root = sqrt_u128(x);
+            assert!(root * root <= x);
+            assert!((root + 1).checked_mul(root + 1).map_or(true, |square| square > x));
+        }
+    }
+
+    #[test]
+    fn test_sqrt_price_x64_matches_f64() {
+        // 1:1 is exactly 2^64
+        assert_eq!(sqrt_price_x64(1_000, 1_000).unwrap(), 1u128 << 64);
+        assert!(sqrt_price_x64(0, 1_000).is_err());
+
+        let mut seed = 0x0f0f_1e1e_2d2d_3c3c;
+        for _ in 0..2000 {
+            let amount_0 = 1 + next_random(&mut seed) % 1_000_000_000_000_000;
+            let amount_1 = (next_random(&mut seed) >> (next_random(&mut seed) % 64)).max(1);
+
+            let result = sqrt_price_x64(amount_0, amount_1).unwrap() as f64;
+            let expected = (amount_1 as f64 / amount_0 as f64).sqrt() * 2f64.powi(64);
+            assert_close(result, expected, 1e-9, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_calculate_fee_amount_bps() {
+        let amount = 80_000_000_000; // 80 SOL
+
+        assert_eq!(calculate_fee_amount(amount, 0).unwrap(), 0);
+        assert_eq!(calculate_fee_amount(amount, 1).unwrap(), 8_000_000); // 0.01%
+        assert_eq!(calculate_fee_amount(amount, 100).unwrap(), 800_000_000); // 1%
+        assert_eq!(calculate_fee_amount(amount, 10_000).unwrap(), amount); // 100%
+
+        // Rounds down, never charging more than the exact fee
+        assert_eq!(calculate_fee_amount(9_999, 1).unwrap(), 0);
+        assert_eq!(calculate_fee_amount(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+}
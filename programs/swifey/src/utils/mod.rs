@@ -5,4 +5,10 @@ pub mod events;
 pub use events::*;
 
 pub mod fixed_math;
-pub use fixed_math::*;
\ No newline at end of file
+pub use fixed_math::*;
+
+pub mod raydium_cpmm;
+pub use raydium_cpmm::*;
+
+#[cfg(test)]
+pub mod test_utils;
@@ -0,0 +1,151 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+
+// Raydium CPMM is called through a hand-built instruction instead of its crate, which pins its
+// own anchor-lang version and would not share account and CPI types with this program
+
+pub mod raydium_cpmm_program {
+    anchor_lang::declare_id!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+}
+
+pub const CPMM_AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
+pub const CPMM_POOL_LP_MINT_SEED: &str = "pool_lp_mint";
+pub const CPMM_POOL_VAULT_SEED: &str = "pool_vault";
+pub const CPMM_OBSERVATION_SEED: &str = "observation";
+
+// Anchor discriminator of initialize: sha256("global:initialize")[..8]
+const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+// Anchor discriminator of the AmmConfig account: sha256("account:AmmConfig")[..8]
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+
+// Leading fields of the CPMM AmmConfig account, up to the fee charged for creating a pool
+#[derive(AnchorDeserialize)]
+struct CpmmAmmConfig {
+    _bump: u8,
+    _disable_create_pool: bool,
+    _index: u16,
+    _trade_fee_rate: u64,
+    _protocol_fee_rate: u64,
+    _fund_fee_rate: u64,
+    create_pool_fee: u64,
+}
+
+// Lamports the pool creator pays to create a CPMM pool with this config
+pub fn read_create_pool_fee(amm_config: &AccountInfo) -> Result<u64> {
+    require_keys_eq!(*amm_config.owner, raydium_cpmm_program::ID, ErrorCode::AccountOwnedByWrongProgram);
+
+    let data = amm_config.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == AMM_CONFIG_DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let config = CpmmAmmConfig::deserialize(&mut &data[8..])?;
+    Ok(config.create_pool_fee)
+}
+
+// Accounts of the CPMM initialize instruction
+pub struct CpmmInitialize<'info> {
+    pub creator: AccountInfo<'info>,
+    pub amm_config: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub pool_state: AccountInfo<'info>,
+    pub token_0_mint: AccountInfo<'info>,
+    pub token_1_mint: AccountInfo<'info>,
+    pub lp_mint: AccountInfo<'info>,
+    pub creator_token_0: AccountInfo<'info>,
+    pub creator_token_1: AccountInfo<'info>,
+    pub creator_lp_token: AccountInfo<'info>,
+    pub token_0_vault: AccountInfo<'info>,
+    pub token_1_vault: AccountInfo<'info>,
+    pub create_pool_fee: AccountInfo<'info>,
+    pub observation_state: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub token_0_program: AccountInfo<'info>,
+    pub token_1_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+}
+
+// Create a CPMM pool and deposit its initial liquidity. The pool state is a keypair signed by
+// the caller, so it is passed as a signer
+pub fn cpmm_initialize<'info>(
+    program: &AccountInfo<'info>,
+    accounts: CpmmInitialize<'info>,
+    init_amount_0: u64,
+    init_amount_1: u64,
+    open_time: u64,
+) -> Result<()> {
+    // (account, is_signer, is_writable), in the order of the instruction
+    let account_list = [
+        (accounts.creator, true, true),
+        (accounts.amm_config, false, false),
+        (accounts.authority, false, false),
+        (accounts.pool_state, true, true),
+        (accounts.token_0_mint, false, false),
+        (accounts.token_1_mint, false, false),
+        (accounts.lp_mint, false, true),
+        (accounts.creator_token_0, false, true),
+        (accounts.creator_token_1, false, true),
+        (accounts.creator_lp_token, false, true),
+        (accounts.token_0_vault, false, true),
+        (accounts.token_1_vault, false, true),
+        (accounts.create_pool_fee, false, true),
+        (accounts.observation_state, false, true),
+        (accounts.token_program, false, false),
+        (accounts.token_0_program, false, false),
+        (accounts.token_1_program, false, false),
+        (accounts.associated_token_program, false, false),
+        (accounts.system_program, false, false),
+        (accounts.rent, false, false),
+    ];
+
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&init_amount_0.to_le_bytes());
+    data.extend_from_slice(&init_amount_1.to_le_bytes());
+    data.extend_from_slice(&open_time.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: program.key(),
+        accounts: account_list
+            .iter()
+            .map(|(info, is_signer, is_writable)| AccountMeta {
+                pubkey: info.key(),
+                is_signer: *is_signer,
+                is_writable: *is_writable,
+            })
+            .collect(),
+        data,
+    };
+
+    let mut account_infos: Vec<AccountInfo<'info>> = account_list
+        .into_iter()
+        .map(|(info, _, _)| info)
+        .collect();
+    account_infos.push(program.clone());
+
+    invoke(&instruction, &account_infos)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn test_initialize_discriminator() {
+        assert_eq!(INITIALIZE_DISCRIMINATOR[..], hash(b"global:initialize").to_bytes()[..8]);
+    }
+
+    #[test]
+    fn test_amm_config_discriminator() {
+        assert_eq!(AMM_CONFIG_DISCRIMINATOR[..], hash(b"account:AmmConfig").to_bytes()[..8]);
+    }
+}
@@ -0,0 +1,18 @@
+// Helpers shared by the randomized tests of the curve math
+
+// xorshift64, deterministic so a failing case can be reproduced from its seed
+pub fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+// Fails unless actual is within max(|expected| * relative, absolute) of expected
+pub fn assert_close(actual: f64, expected: f64, relative: f64, absolute: f64) {
+    let tolerance = (expected.abs() * relative).max(absolute);
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "actual={} expected={} tolerance={}", actual, expected, tolerance
+    );
+}
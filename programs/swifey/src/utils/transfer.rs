@@ -81,3 +81,112 @@ pub fn token_transfer_with_signer<'info>(
 
     Ok(())
 }
+
+pub fn token_approve_with_signer<'info>(
+    account: &AccountInfo<'info>,
+    delegate: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::Approve {
+            to: account.to_account_info(),
+            delegate: delegate.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::approve(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+pub fn token_revoke_with_signer<'info>(
+    account: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::Revoke {
+            source: account.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::revoke(cpi_ctx)?;
+
+    Ok(())
+}
+
+pub fn token_burn_with_signer<'info>(
+    mint: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::Burn {
+            mint: mint.to_account_info(),
+            from: from.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::burn(cpi_ctx, amount)?;
+
+    Ok(())
+}
+
+pub fn token_close_with_signer<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_ctx: CpiContext<_> = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: account.to_account_info(),
+            destination: destination.to_account_info(),
+            authority: authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::close_account(cpi_ctx)?;
+
+    Ok(())
+}
+
+// Grow a program-owned account from old_len to new_len, with the payer covering the extra rent
+// so the lamports the account already held stay with it
+pub fn realloc_with_rent<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &AccountInfo<'info>,
+    old_len: usize,
+    new_len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let extra_rent = rent
+        .minimum_balance(new_len)
+        .saturating_sub(rent.minimum_balance(old_len));
+    if extra_rent > 0 {
+        sol_transfer_from_user(payer, account, system_program, extra_rent)?;
+    }
+
+    account.realloc(new_len, false)?;
+    Ok(())
+}
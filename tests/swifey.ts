@@ -27,6 +27,18 @@ chai.use(chaiBN);
 const METADATA_PROGRAM_ID = new PublicKey(
   "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
 );
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+const CLMM_PROGRAM_ID = new PublicKey(
+  "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK"
+);
+const CPMM_PROGRAM_ID = new PublicKey(
+  "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C"
+);
+const CPMM_CREATE_POOL_FEE_RECEIVER = new PublicKey(
+  "DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8"
+);
 
 describe("swifey", () => {
   const provider = anchor.AnchorProvider.env();
@@ -37,7 +49,29 @@ describe("swifey", () => {
   const user = Keypair.generate();
 
   let configPda: PublicKey;
+
+  // Config changes are queued, and can be executed once the config timelock expires
+  const CONFIG_TIMELOCK = 1; // seconds
+  const MIN_UNWIND_DELAY = 86_400; // seconds, matches the program constant
+  const UNPAUSED = { launch: false, buy: false, sell: false, migrate: false };
+  const executeConfig = async () => {
+    await new Promise((resolve) =>
+      setTimeout(resolve, (CONFIG_TIMELOCK + 1) * 1000)
+    );
+    await program.methods
+      .executeConfig()
+      .accounts({
+        executor: creator.publicKey,
+        globalConfig: configPda,
+        pendingConfig: pendingConfigPda,
+        proposer: creator.publicKey,
+      })
+      .signers([creator])
+      .rpc();
+  };
   let configBump: number;
+  let feeVaultPda: PublicKey;
+  let pendingConfigPda: PublicKey;
   let tokenMint: Keypair;
   let bondingCurvePda: PublicKey;
   let curveTokenAccount: PublicKey;
@@ -45,13 +79,22 @@ describe("swifey", () => {
   let metadataPda: PublicKey;
   let wsolMint: PublicKey;
   let ammConfig: PublicKey;
+  let cpmmAmmConfig: PublicKey;
 
   before(async () => {
     // Initialize WSOL mint (this is a well-known address on devnet/mainnet)
     wsolMint = new PublicKey("So11111111111111111111111111111111111111112");
 
-    // Use the actual Raydium AMM config
-    ammConfig = new PublicKey("GVSwm4smQBYcgAJU7qjFHLQBHTc4AdB3F2HbZp6KqKof");
+    // Raydium AMM configs at index 0, cloned from mainnet by Anchor.toml
+    const ammConfigIndex = new BN(0).toArrayLike(Buffer, "be", 2);
+    [ammConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("amm_config"), ammConfigIndex],
+      CLMM_PROGRAM_ID
+    );
+    [cpmmAmmConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("amm_config"), ammConfigIndex],
+      CPMM_PROGRAM_ID
+    );
 
     // Airdrop SOL to creator and user
     await provider.connection.requestAirdrop(
@@ -74,6 +117,16 @@ describe("swifey", () => {
     configPda = configAddress;
     configBump = bump;
 
+    [feeVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_vault")],
+      program.programId
+    );
+
+    [pendingConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pending_config")],
+      program.programId
+    );
+
     tokenMint = Keypair.generate();
 
     [bondingCurvePda] = PublicKey.findProgramAddressSync(
@@ -106,25 +159,39 @@ describe("swifey", () => {
       curveLimit: CURVE_LIMIT,
       initialVirtualTokenReserve: TOTAL_SUPPLY,
       initialVirtualSolReserve: INITIAL_SOL,
-      initialRealTokenReserve: new BN(0),
       totalTokenSupply: TOTAL_SUPPLY,
       buyFeePercentage: new BN(100), // 1%
       sellFeePercentage: new BN(100), // 1%
       migrationFeePercentage: new BN(100), // 1%
+      creatorFeePercentage: new BN(50), // 0.5%
+      keeperRewardPercentage: new BN(1000), // 10% of the migration fee
+      lpPolicy: { lock: {} },
+      migrationTarget: { raydiumClmm: {} },
+      clmmAmmConfig: ammConfig,
+      cpmmAmmConfig: cpmmAmmConfig,
       maxPriceImpact: new BN(10000000000), // 100%
-      isPaused: false,
+      configTimelock: new BN(CONFIG_TIMELOCK),
+      pauseFlags: UNPAUSED,
       reserved: reserved,
     };
 
+    // Only the upgrade authority of the program can initialize the config
+    const [programDataPda] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    );
+
     try {
       await program.methods
-        .configure(configSettings)
+        .initialize(configSettings)
         .accounts({
-          admin: creator.publicKey,
+          admin: provider.wallet.publicKey,
           globalConfig: configPda,
+          feeVault: feeVaultPda,
+          program: program.programId,
+          programData: programDataPda,
           systemProgram: SystemProgram.programId,
         })
-        .signers([creator])
         .rpc();
     } catch (error) {
       // If the account is already initialized, that's fine
@@ -158,13 +225,19 @@ describe("swifey", () => {
           curveLimit: CURVE_LIMIT,
           initialVirtualTokenReserve: TOTAL_SUPPLY,
           initialVirtualSolReserve: INITIAL_SOL,
-          initialRealTokenReserve: new BN(0),
           totalTokenSupply: TOTAL_SUPPLY,
           buyFeePercentage: new BN(100), // 1%
           sellFeePercentage: new BN(100), // 1%
           migrationFeePercentage: new BN(100), // 1%
+          creatorFeePercentage: new BN(50), // 0.5%
+          keeperRewardPercentage: new BN(1000), // 10% of the migration fee
+          lpPolicy: { lock: {} },
+          migrationTarget: { raydiumClmm: {} },
+          clmmAmmConfig: ammConfig,
+          cpmmAmmConfig: cpmmAmmConfig,
           maxPriceImpact: new BN(10000000000), // 100%
-          isPaused: false,
+          configTimelock: new BN(CONFIG_TIMELOCK),
+          pauseFlags: UNPAUSED,
           reserved: reserved,
         };
 
@@ -173,10 +246,12 @@ describe("swifey", () => {
           .accounts({
             admin: creator.publicKey,
             globalConfig: configPda,
+            pendingConfig: pendingConfigPda,
             systemProgram: SystemProgram.programId,
           })
           .signers([creator])
           .rpc();
+        await executeConfig();
 
         const config = await program.account.config.fetch(configPda);
         expect(config.authority.toString()).to.equal(
@@ -204,10 +279,16 @@ describe("swifey", () => {
         console.log(bondingCurvePda);
 
         await program.methods
-          .launch("Swifey Token", "SWFY", "https://swifey.io/metadata.json")
+          .launch(
+            "Swifey Token",
+            "SWFY",
+            "https://swifey.io/metadata.json",
+            null
+          )
           .accounts({
             creator: creator.publicKey,
             globalConfig: configPda,
+            curveTemplate: null,
             tokenMint: tokenMint.publicKey,
             bondingCurve: bondingCurvePda,
             curveTokenAccount: curveTokenAccount,
@@ -228,12 +309,150 @@ describe("swifey", () => {
         console.log(bondingCurve);
         expect(bondingCurve.isCompleted).to.be.false;
         expect(bondingCurve.isMigrated).to.be.false;
+
+        // Curve parameters are frozen from the config at launch
+        const config = await program.account.config.fetch(configPda);
+        expect(bondingCurve.curveLimit.eq(config.curveLimit)).to.be.true;
+        expect(
+          bondingCurve.initialVirtualSolReserve.eq(config.initialVirtualSolReserve)
+        ).to.be.true;
+        expect(bondingCurve.buyFeePercentage.eq(config.buyFeePercentage)).to.be
+          .true;
       } catch (error) {
         console.error("Launch error:", error);
         throw error;
       }
     });
 
+    it("Can launch a token with a curve template", async () => {
+      try {
+        const templateIndex = 1;
+        const [curveTemplatePda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("curve_template"),
+            new BN(templateIndex).toArrayLike(Buffer, "le", 2),
+          ],
+          program.programId
+        );
+
+        const TOTAL_SUPPLY = new BN("1000000000").mul(new BN(10).pow(new BN(6)));
+        const params = {
+          curveLimit: new BN(40 * anchor.web3.LAMPORTS_PER_SOL),
+          initialVirtualTokenReserve: TOTAL_SUPPLY,
+          initialVirtualSolReserve: new BN(2 * anchor.web3.LAMPORTS_PER_SOL),
+          totalTokenSupply: TOTAL_SUPPLY,
+          buyFeePercentage: new BN(50), // 0.5%
+          sellFeePercentage: new BN(50), // 0.5%
+          migrationFeePercentage: new BN(100), // 1%
+          creatorFeePercentage: new BN(0),
+        };
+
+        await program.methods
+          .configureTemplate(templateIndex, params, true)
+          .accounts({
+            authority: creator.publicKey,
+            globalConfig: configPda,
+            curveTemplate: curveTemplatePda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        const templateMint = Keypair.generate();
+        const [templateCurvePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("bonding_curve"), templateMint.publicKey.toBuffer()],
+          program.programId
+        );
+        const [templateMetadataPda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("metadata"),
+            METADATA_PROGRAM_ID.toBuffer(),
+            templateMint.publicKey.toBuffer(),
+          ],
+          METADATA_PROGRAM_ID
+        );
+
+        await program.methods
+          .launch(
+            "Swifey Tier",
+            "SWFT",
+            "https://swifey.io/metadata.json",
+            templateIndex
+          )
+          .accounts({
+            creator: creator.publicKey,
+            globalConfig: configPda,
+            curveTemplate: curveTemplatePda,
+            tokenMint: templateMint.publicKey,
+            bondingCurve: templateCurvePda,
+            curveTokenAccount: await getAssociatedTokenAddress(
+              templateMint.publicKey,
+              templateCurvePda,
+              true
+            ),
+            tokenMetadataAccount: templateMetadataPda,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            metadataProgram: METADATA_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([creator, templateMint])
+          .rpc();
+
+        const bondingCurve = await program.account.bondingCurve.fetch(
+          templateCurvePda
+        );
+        expect(bondingCurve.curveLimit.eq(params.curveLimit)).to.be.true;
+        expect(bondingCurve.buyFeePercentage.eq(params.buyFeePercentage)).to.be
+          .true;
+
+        // The template account must be the PDA of the requested index
+        const otherMint = Keypair.generate();
+        const [otherCurvePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("bonding_curve"), otherMint.publicKey.toBuffer()],
+          program.programId
+        );
+        try {
+          await program.methods
+            .launch("Swifey Tier", "SWFT", "https://swifey.io/metadata.json", 2)
+            .accounts({
+              creator: creator.publicKey,
+              globalConfig: configPda,
+              curveTemplate: curveTemplatePda,
+              tokenMint: otherMint.publicKey,
+              bondingCurve: otherCurvePda,
+              curveTokenAccount: await getAssociatedTokenAddress(
+                otherMint.publicKey,
+                otherCurvePda,
+                true
+              ),
+              tokenMetadataAccount: PublicKey.findProgramAddressSync(
+                [
+                  Buffer.from("metadata"),
+                  METADATA_PROGRAM_ID.toBuffer(),
+                  otherMint.publicKey.toBuffer(),
+                ],
+                METADATA_PROGRAM_ID
+              )[0],
+              tokenProgram: TOKEN_PROGRAM_ID,
+              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+              metadataProgram: METADATA_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+              rent: SYSVAR_RENT_PUBKEY,
+            })
+            .signers([creator, otherMint])
+            .rpc();
+          assert.fail("Should have rejected a template of another index");
+        } catch (error) {
+          expect(error.toString()).to.include("ConstraintSeeds");
+        }
+      } catch (error) {
+        console.error("Template launch error:", error);
+        throw error;
+      }
+    });
+
     // it("Can buy tokens", async () => {
     //   try {
     //     userTokenAccount = await getAssociatedTokenAddress(
@@ -248,7 +467,7 @@ describe("swifey", () => {
     //       .accounts({
     //         user: user.publicKey,
     //         globalConfig: configPda,
-    //         feeRecipient: creator.publicKey,
+    //         feeVault: feeVaultPda,
     //         bondingCurve: bondingCurvePda,
     //         tokenMint: tokenMint.publicKey,
     //         curveTokenAccount: curveTokenAccount,
@@ -285,7 +504,7 @@ describe("swifey", () => {
           .accounts({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -308,6 +527,19 @@ describe("swifey", () => {
         console.log(`Received ${userBalance.value.amount} tokens for 72 SOL`);
         console.log("Token balance details:", userBalance.value);
         expect(Number(userBalance.value.amount)).to.be.greaterThan(0);
+
+        // Real reserves track what the curve actually holds
+        const bondingCurve = await program.account.bondingCurve.fetch(
+          bondingCurvePda
+        );
+        const curveTokenBalance =
+          await provider.connection.getTokenAccountBalance(curveTokenAccount);
+        expect(bondingCurve.realSolReserve.toString()).to.equal(
+          buyAmount.toString()
+        );
+        expect(bondingCurve.realTokenReserve.toString()).to.equal(
+          curveTokenBalance.value.amount
+        );
       } catch (error) {
         console.error("Buy error:", error);
         throw error;
@@ -322,8 +554,8 @@ describe("swifey", () => {
         const userSolBalanceBefore = await provider.connection.getBalance(
           user.publicKey
         );
-        const feeRecipientBalanceBefore = await provider.connection.getBalance(
-          creator.publicKey
+        const feeVaultBalanceBefore = await provider.connection.getBalance(
+          feeVaultPda
         );
         const pdaBalanceBefore = await provider.connection.getBalance(
           bondingCurvePda
@@ -339,8 +571,8 @@ describe("swifey", () => {
           } SOL`
         );
         console.log(
-          `Fee Recipient SOL Balance: ${
-            feeRecipientBalanceBefore / anchor.web3.LAMPORTS_PER_SOL
+          `Fee Vault SOL Balance: ${
+            feeVaultBalanceBefore / anchor.web3.LAMPORTS_PER_SOL
           } SOL`
         );
         console.log(
@@ -357,7 +589,7 @@ describe("swifey", () => {
           .accounts({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -378,8 +610,8 @@ describe("swifey", () => {
         const userSolBalanceAfter = await provider.connection.getBalance(
           user.publicKey
         );
-        const feeRecipientBalanceAfter = await provider.connection.getBalance(
-          creator.publicKey
+        const feeVaultBalanceAfter = await provider.connection.getBalance(
+          feeVaultPda
         );
         const pdaBalanceAfter = await provider.connection.getBalance(
           bondingCurvePda
@@ -397,8 +629,8 @@ describe("swifey", () => {
           } SOL`
         );
         console.log(
-          `Fee Recipient SOL Balance: ${
-            feeRecipientBalanceAfter / anchor.web3.LAMPORTS_PER_SOL
+          `Fee Vault SOL Balance: ${
+            feeVaultBalanceAfter / anchor.web3.LAMPORTS_PER_SOL
           } SOL`
         );
         console.log(
@@ -415,8 +647,8 @@ describe("swifey", () => {
           } SOL`
         );
         console.log(
-          `SOL transferred to fee recipient: ${
-            (feeRecipientBalanceAfter - feeRecipientBalanceBefore) /
+          `SOL transferred to fee vault: ${
+            (feeVaultBalanceAfter - feeVaultBalanceBefore) /
             anchor.web3.LAMPORTS_PER_SOL
           } SOL`
         );
@@ -433,8 +665,8 @@ describe("swifey", () => {
         );
 
         expect(userSolBalanceAfter).to.be.greaterThan(userSolBalanceBefore);
-        expect(feeRecipientBalanceAfter).to.be.greaterThan(
-          feeRecipientBalanceBefore
+        expect(feeVaultBalanceAfter).to.be.greaterThan(
+          feeVaultBalanceBefore
         );
       } catch (error) {
         console.error("Sell error:", error);
@@ -442,6 +674,150 @@ describe("swifey", () => {
       }
     });
 
+    it("Creator can claim accrued creator fees", async () => {
+      try {
+        const bondingCurve = await program.account.bondingCurve.fetch(
+          bondingCurvePda
+        );
+        expect(bondingCurve.creator.toString()).to.equal(
+          creator.publicKey.toString()
+        );
+        expect(bondingCurve.creatorFeesAccrued.toNumber()).to.be.greaterThan(0);
+
+        const creatorBalanceBefore = await provider.connection.getBalance(
+          creator.publicKey
+        );
+
+        await program.methods
+          .claimCreatorFees()
+          .accounts({
+            creator: creator.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        const creatorBalanceAfter = await provider.connection.getBalance(
+          creator.publicKey
+        );
+        expect(creatorBalanceAfter).to.be.greaterThan(creatorBalanceBefore);
+
+        const bondingCurveAfter = await program.account.bondingCurve.fetch(
+          bondingCurvePda
+        );
+        expect(bondingCurveAfter.creatorFeesAccrued.toNumber()).to.equal(0);
+      } catch (error) {
+        console.error("Creator fee claim error:", error);
+        throw error;
+      }
+    });
+
+    it("Authority can claim platform fees from the fee vault", async () => {
+      try {
+        const feeVault = await program.account.feeVault.fetch(feeVaultPda);
+        const unclaimed = feeVault.totalAccrued.sub(feeVault.totalClaimed);
+        expect(unclaimed.toNumber()).to.be.greaterThan(0);
+
+        const recipientBalanceBefore = await provider.connection.getBalance(
+          creator.publicKey
+        );
+
+        await program.methods
+          .claimFees()
+          .accounts({
+            authority: creator.publicKey,
+            globalConfig: configPda,
+            feeVault: feeVaultPda,
+            feeRecipient: creator.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        const recipientBalanceAfter = await provider.connection.getBalance(
+          creator.publicKey
+        );
+        expect(recipientBalanceAfter).to.be.greaterThan(recipientBalanceBefore);
+
+        const feeVaultAfter = await program.account.feeVault.fetch(feeVaultPda);
+        expect(feeVaultAfter.totalClaimed.toString()).to.equal(
+          feeVaultAfter.totalAccrued.toString()
+        );
+      } catch (error) {
+        console.error("Fee vault claim error:", error);
+        throw error;
+      }
+    });
+
+    it("Can buy and sell exact output amounts", async () => {
+      try {
+        const swapAccounts = {
+          user: user.publicKey,
+          globalConfig: configPda,
+          feeVault: feeVaultPda,
+          bondingCurve: bondingCurvePda,
+          tokenMint: tokenMint.publicKey,
+          curveTokenAccount: curveTokenAccount,
+          userTokenAccount: userTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        };
+
+        const tokenBalanceBefore =
+          await provider.connection.getTokenAccountBalance(userTokenAccount);
+        const exactTokens = new BN(1_000_000_000_000); // 1M tokens
+
+        await program.methods
+          .swapExactOut(exactTokens, 0, new BN(10 * anchor.web3.LAMPORTS_PER_SOL)) // direction 0 for buy
+          .accounts(swapAccounts)
+          .signers([user])
+          .preInstructions([
+            anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+              units: 1000000,
+            }),
+          ])
+          .rpc();
+
+        const tokenBalanceAfter =
+          await provider.connection.getTokenAccountBalance(userTokenAccount);
+        expect(
+          new BN(tokenBalanceAfter.value.amount).sub(
+            new BN(tokenBalanceBefore.value.amount)
+          )
+        ).to.be.bignumber.equal(exactTokens);
+
+        const exactSol = new BN(0.01 * anchor.web3.LAMPORTS_PER_SOL);
+        const solBalanceBefore = await provider.connection.getBalance(
+          user.publicKey
+        );
+
+        await program.methods
+          .swapExactOut(exactSol, 1, exactTokens) // direction 1 for sell
+          .accounts(swapAccounts)
+          .signers([user])
+          .preInstructions([
+            anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+              units: 1000000,
+            }),
+          ])
+          .rpc();
+
+        // The user pays the transaction fee, so allow for it
+        const solBalanceAfter = await provider.connection.getBalance(
+          user.publicKey
+        );
+        expect(solBalanceAfter - solBalanceBefore).to.be.greaterThan(
+          exactSol.toNumber() - 10_000
+        );
+      } catch (error) {
+        console.error("Exact output swap error:", error);
+        throw error;
+      }
+    });
+
     it("Can perform multiple buys and sells", async () => {
       try {
         // Reset the curve by relaunching
@@ -470,10 +846,16 @@ describe("swifey", () => {
 
         // Relaunch token
         await program.methods
-          .launch("Swifey Token", "SWFY", "https://swifey.io/metadata.json")
+          .launch(
+            "Swifey Token",
+            "SWFY",
+            "https://swifey.io/metadata.json",
+            null
+          )
           .accounts({
             creator: creator.publicKey,
             globalConfig: configPda,
+            curveTemplate: null,
             tokenMint: tokenMint.publicKey,
             bondingCurve: bondingCurvePda,
             curveTokenAccount: curveTokenAccount,
@@ -496,7 +878,7 @@ describe("swifey", () => {
           .accounts({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -522,7 +904,7 @@ describe("swifey", () => {
           .accounts({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -548,7 +930,7 @@ describe("swifey", () => {
           .accounts({
             user: user.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -598,7 +980,7 @@ describe("swifey", () => {
             .accounts({
               user: poorUser.publicKey,
               globalConfig: configPda,
-              feeRecipient: creator.publicKey,
+              feeVault: feeVaultPda,
               bondingCurve: bondingCurvePda,
               tokenMint: tokenMint.publicKey,
               curveTokenAccount: curveTokenAccount,
@@ -641,7 +1023,7 @@ describe("swifey", () => {
           .accounts({
             user: noTokenUser.publicKey,
             globalConfig: configPda,
-            feeRecipient: creator.publicKey,
+            feeVault: feeVaultPda,
             bondingCurve: bondingCurvePda,
             tokenMint: tokenMint.publicKey,
             curveTokenAccount: curveTokenAccount,
@@ -661,7 +1043,7 @@ describe("swifey", () => {
             .accounts({
               user: noTokenUser.publicKey,
               globalConfig: configPda,
-              feeRecipient: creator.publicKey,
+              feeVault: feeVaultPda,
               bondingCurve: bondingCurvePda,
               tokenMint: tokenMint.publicKey,
               curveTokenAccount: curveTokenAccount,
@@ -685,5 +1067,897 @@ describe("swifey", () => {
         throw error;
       }
     });
+
+    it("Should fail when price impact exceeds the configured limit", async () => {
+      const config = await program.account.config.fetch(configPda);
+      const configure = async (maxPriceImpact: BN) => {
+        await program.methods
+          .configure({ ...config, maxPriceImpact })
+          .accounts({
+            admin: creator.publicKey,
+            globalConfig: configPda,
+            pendingConfig: pendingConfigPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        await executeConfig();
+      };
+
+      // A zero limit would reject every swap
+      try {
+        await configure(new BN(0));
+        assert.fail("Should have rejected a zero max price impact");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidPriceImpact");
+      }
+
+      // Allow at most 0.01% price impact
+      await configure(new BN(1));
+      try {
+        await program.methods
+          .swap(new BN(1 * anchor.web3.LAMPORTS_PER_SOL), 0, new BN(0))
+          .accounts({
+            user: user.publicKey,
+            globalConfig: configPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            userTokenAccount: userTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Should have failed due to excessive price impact");
+      } catch (error) {
+        expect(error.toString()).to.include("ExcessivePriceImpact");
+      } finally {
+        await configure(config.maxPriceImpact);
+      }
+    });
+
+    it("Can pause buys only and update fees with the granular admin instructions", async () => {
+      const setPause = (pauseFlags: typeof UNPAUSED) =>
+        program.methods
+          .setPause(pauseFlags)
+          .accounts({ authority: creator.publicKey, globalConfig: configPda })
+          .signers([creator])
+          .rpc();
+      const swap = (amount: BN, direction: number) =>
+        program.methods
+          .swap(amount, direction, new BN(0))
+          .accounts({
+            user: user.publicKey,
+            globalConfig: configPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            userTokenAccount: userTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+      // Holders can still exit while buys are paused
+      await setPause({ ...UNPAUSED, buy: true });
+      try {
+        try {
+          await swap(new BN(0.1 * anchor.web3.LAMPORTS_PER_SOL), 0);
+          assert.fail("Should have failed while buys are paused");
+        } catch (error) {
+          expect(error.toString()).to.include("BuysPaused");
+        }
+        await swap(new BN(1_000_000), 1); // 1 token
+      } finally {
+        await setPause(UNPAUSED);
+      }
+
+      const config = await program.account.config.fetch(configPda);
+      const fees = {
+        buyFeePercentage: config.buyFeePercentage,
+        sellFeePercentage: config.sellFeePercentage,
+        migrationFeePercentage: config.migrationFeePercentage,
+        creatorFeePercentage: config.creatorFeePercentage,
+        keeperRewardPercentage: config.keeperRewardPercentage,
+      };
+
+      // Buy fee plus creator fee above 100% is rejected
+      try {
+        await program.methods
+          .setFees({ ...fees, buyFeePercentage: new BN(9990) })
+          .accounts({
+            authority: creator.publicKey,
+            globalConfig: configPda,
+            pendingConfig: pendingConfigPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        assert.fail("Should have rejected the fees");
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidFeePercentage");
+      }
+
+      await program.methods
+        .setFees({ ...fees, sellFeePercentage: new BN(200) })
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          pendingConfig: pendingConfigPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+      await executeConfig();
+      const updated = await program.account.config.fetch(configPda);
+      expect(updated.sellFeePercentage.toNumber()).to.equal(200);
+      expect(updated.curveLimit.toString()).to.equal(config.curveLimit.toString());
+    });
+
+    it("Lets a pauser toggle the pause without any other admin rights", async () => {
+      const setRole = (role: object, account: PublicKey) =>
+        program.methods
+          .setRole(role as any, account)
+          .accounts({ authority: creator.publicKey, globalConfig: configPda })
+          .signers([creator])
+          .rpc();
+
+      await setRole({ pauser: {} }, user.publicKey);
+      try {
+        for (const isPaused of [true, false]) {
+          await program.methods
+            .setPause({ ...UNPAUSED, sell: isPaused })
+            .accounts({ authority: user.publicKey, globalConfig: configPda })
+            .signers([user])
+            .rpc();
+          const config = await program.account.config.fetch(configPda);
+          expect(config.pauseFlags.sell).to.equal(isPaused);
+        }
+
+        const config = await program.account.config.fetch(configPda);
+        try {
+          await program.methods
+            .setFees({
+              buyFeePercentage: config.buyFeePercentage,
+              sellFeePercentage: config.sellFeePercentage,
+              migrationFeePercentage: config.migrationFeePercentage,
+              creatorFeePercentage: config.creatorFeePercentage,
+              keeperRewardPercentage: config.keeperRewardPercentage,
+            })
+            .accounts({
+              authority: user.publicKey,
+              globalConfig: configPda,
+              pendingConfig: pendingConfigPda,
+              systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc();
+          assert.fail("A pauser should not be able to set fees");
+        } catch (error) {
+          expect(error.toString()).to.include("UnauthorizedAddress");
+        }
+      } finally {
+        await setRole({ pauser: {} }, PublicKey.default);
+      }
+    });
+
+    it("Halts trading on a single curve with its status", async () => {
+      const setCurveStatus = (status: object) =>
+        program.methods
+          .setCurveStatus(status as any)
+          .accounts({
+            authority: creator.publicKey,
+            globalConfig: configPda,
+            tokenMint: tokenMint.publicKey,
+            bondingCurve: bondingCurvePda,
+          })
+          .signers([creator])
+          .rpc();
+      const buy = async () =>
+        program.methods
+          .swap(new BN(0.01 * anchor.web3.LAMPORTS_PER_SOL), 0, new BN(0))
+          .accounts({
+            user: user.publicKey,
+            globalConfig: configPda,
+            feeVault: feeVaultPda,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            userTokenAccount: await getAssociatedTokenAddress(
+              tokenMint.publicKey,
+              user.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+
+      for (const status of [{ paused: {} }, { frozen: {} }]) {
+        await setCurveStatus(status);
+        try {
+          await buy();
+          assert.fail("Should have failed while the curve is halted");
+        } catch (error) {
+          expect(error.toString()).to.include("CurveNotActive");
+        }
+      }
+
+      await setCurveStatus({ active: {} });
+      await buy();
+      const curve = await program.account.bondingCurve.fetch(bondingCurvePda);
+      expect(curve.status).to.deep.equal({ active: {} });
+    });
+
+    it("Transfers the authority only once the proposed key accepts", async () => {
+      const newAuthority = Keypair.generate();
+      const propose = (authority: Keypair, pending: PublicKey) =>
+        program.methods
+          .proposeAuthority(pending)
+          .accounts({ authority: authority.publicKey, globalConfig: configPda })
+          .signers([authority])
+          .rpc();
+      const accept = (authority: Keypair) =>
+        program.methods
+          .acceptAuthority()
+          .accounts({ newAuthority: authority.publicKey, globalConfig: configPda })
+          .signers([authority])
+          .rpc();
+
+      // A cancelled proposal can no longer be accepted
+      await propose(creator, newAuthority.publicKey);
+      await program.methods
+        .cancelAuthority()
+        .accounts({ authority: creator.publicKey, globalConfig: configPda })
+        .signers([creator])
+        .rpc();
+      try {
+        await accept(newAuthority);
+        assert.fail("Should have failed without a pending authority");
+      } catch (error) {
+        expect(error.toString()).to.include("NoPendingAuthority");
+      }
+
+      // Only the proposed key can accept
+      await propose(creator, newAuthority.publicKey);
+      try {
+        await accept(user);
+        assert.fail("Should have failed for a key that was not proposed");
+      } catch (error) {
+        expect(error.toString()).to.include("UnauthorizedAddress");
+      }
+
+      await accept(newAuthority);
+      let config = await program.account.config.fetch(configPda);
+      expect(config.authority.toString()).to.equal(newAuthority.publicKey.toString());
+      expect(config.pendingAuthority.toString()).to.equal(PublicKey.default.toString());
+
+      // Hand the authority back for the remaining tests
+      await propose(newAuthority, creator.publicKey);
+      await accept(creator);
+      config = await program.account.config.fetch(configPda);
+      expect(config.authority.toString()).to.equal(creator.publicKey.toString());
+    });
+
+    it("Runs admin instructions only once the multisig approves them", async () => {
+      const [multisigPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("multisig")],
+        program.programId
+      );
+      const [multisigSigner] = PublicKey.findProgramAddressSync(
+        [Buffer.from("multisig_signer")],
+        program.programId
+      );
+
+      // 2-of-2 between creator and user
+      await program.methods
+        .createMultisig([creator.publicKey, user.publicKey], 2)
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          multisig: multisigPda,
+          multisigSigner,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const propose = async (ix: anchor.web3.TransactionInstruction) => {
+        const { proposalCount } = await program.account.multisig.fetch(multisigPda);
+        const [proposal] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("admin_proposal"),
+            proposalCount.toArrayLike(Buffer, "le", 8),
+          ],
+          program.programId
+        );
+        await program.methods
+          .createProposal(ix.keys, ix.data)
+          .accounts({
+            proposer: creator.publicKey,
+            multisig: multisigPda,
+            proposal,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        return proposal;
+      };
+      const approve = (proposal: PublicKey) =>
+        program.methods
+          .approveProposal()
+          .accounts({ approver: user.publicKey, multisig: multisigPda, proposal })
+          .signers([user])
+          .rpc();
+      const execute = (proposal: PublicKey, ix: anchor.web3.TransactionInstruction) =>
+        program.methods
+          .executeProposal()
+          .accounts({
+            executor: creator.publicKey,
+            multisig: multisigPda,
+            proposal,
+            multisigSigner,
+            program: program.programId,
+          })
+          .remainingAccounts(
+            ix.keys.map(({ pubkey, isWritable }) => ({ pubkey, isWritable, isSigner: false }))
+          )
+          .signers([creator])
+          .rpc();
+
+      // Hand the authority to the multisig signer
+      await program.methods
+        .proposeAuthority(multisigSigner)
+        .accounts({ authority: creator.publicKey, globalConfig: configPda })
+        .signers([creator])
+        .rpc();
+      const acceptIx = await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: multisigSigner, globalConfig: configPda })
+        .instruction();
+      const acceptProposal = await propose(acceptIx);
+      try {
+        await execute(acceptProposal, acceptIx);
+        assert.fail("Should have failed with a single approval");
+      } catch (error) {
+        expect(error.toString()).to.include("NotEnoughApprovals");
+      }
+      await approve(acceptProposal);
+      await execute(acceptProposal, acceptIx);
+      let config = await program.account.config.fetch(configPda);
+      expect(config.authority.toString()).to.equal(multisigSigner.toString());
+
+      // A single key can no longer act as the authority
+      try {
+        await program.methods
+          .setPause({ ...UNPAUSED, launch: true })
+          .accounts({ authority: creator.publicKey, globalConfig: configPda })
+          .signers([creator])
+          .rpc();
+        assert.fail("A single approver should not be able to pause");
+      } catch (error) {
+        expect(error.toString()).to.include("UnauthorizedAddress");
+      }
+
+      // Hand the authority back for the remaining tests
+      const proposeIx = await program.methods
+        .proposeAuthority(creator.publicKey)
+        .accounts({ authority: multisigSigner, globalConfig: configPda })
+        .instruction();
+      const proposeProposal = await propose(proposeIx);
+      await approve(proposeProposal);
+      await execute(proposeProposal, proposeIx);
+      try {
+        await execute(proposeProposal, proposeIx);
+        assert.fail("Should not execute a proposal twice");
+      } catch (error) {
+        expect(error.toString()).to.include("ProposalAlreadyExecuted");
+      }
+      await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: creator.publicKey, globalConfig: configPda })
+        .signers([creator])
+        .rpc();
+      config = await program.account.config.fetch(configPda);
+      expect(config.authority.toString()).to.equal(creator.publicKey.toString());
+    });
+
+    it("Holds redemptions until the unwind delay passes", async () => {
+      const userTokenAccount = await getAssociatedTokenAddress(
+        tokenMint.publicKey,
+        user.publicKey
+      );
+      const balance = new BN(
+        (await provider.connection.getTokenAccountBalance(userTokenAccount)).value.amount
+      );
+
+      const startedAt = Math.floor(Date.now() / 1000);
+      await program.methods
+        .startUnwind()
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          tokenMint: tokenMint.publicKey,
+          bondingCurve: bondingCurvePda,
+        })
+        .signers([creator])
+        .rpc();
+
+      // A short config timelock does not shorten the notice holders get
+      const unwinding = await program.account.bondingCurve.fetch(bondingCurvePda);
+      expect(unwinding.status).to.deep.equal({ unwinding: {} });
+      expect(unwinding.unwindAt.toNumber()).to.be.at.least(
+        startedAt + MIN_UNWIND_DELAY - 60
+      );
+
+      try {
+        await program.methods
+          .redeem(balance.divn(2))
+          .accounts({
+            user: user.publicKey,
+            bondingCurve: bondingCurvePda,
+            tokenMint: tokenMint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            userTokenAccount: userTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Should have failed before the unwind opened");
+      } catch (error) {
+        expect(error.toString()).to.include("UnwindNotReady");
+      }
+
+      // The unwind can still be called off before redemptions open
+      await program.methods
+        .setCurveStatus({ active: {} })
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          tokenMint: tokenMint.publicKey,
+          bondingCurve: bondingCurvePda,
+        })
+        .signers([creator])
+        .rpc();
+
+      const resumed = await program.account.bondingCurve.fetch(bondingCurvePda);
+      expect(resumed.status).to.deep.equal({ active: {} });
+      expect(resumed.unwindAt.toNumber()).to.equal(0);
+      expect(resumed.realSolReserve.toString()).to.equal(
+        unwinding.realSolReserve.toString()
+      );
+    });
+
+    it("Migrates completed curves into Raydium under both LP policies", async () => {
+      const keeper = Keypair.generate();
+      await provider.connection.requestAirdrop(
+        keeper.publicKey,
+        100 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      const [lpLockPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp_lock")],
+        program.programId
+      );
+      const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+        units: 1400000,
+      });
+
+      // Small curve, whose 10% migration fee leaves a keeper reward above the CPMM pool fee
+      const templateIndex = 4;
+      const [curveTemplatePda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("curve_template"),
+          new BN(templateIndex).toArrayLike(Buffer, "le", 2),
+        ],
+        program.programId
+      );
+      const TOTAL_SUPPLY = new BN("1000000000").mul(new BN(10).pow(new BN(6)));
+      await program.methods
+        .configureTemplate(
+          templateIndex,
+          {
+            curveLimit: new BN(6 * anchor.web3.LAMPORTS_PER_SOL),
+            initialVirtualTokenReserve: TOTAL_SUPPLY,
+            initialVirtualSolReserve: new BN(anchor.web3.LAMPORTS_PER_SOL),
+            totalTokenSupply: TOTAL_SUPPLY,
+            buyFeePercentage: new BN(100), // 1%
+            sellFeePercentage: new BN(100), // 1%
+            migrationFeePercentage: new BN(1000), // 10%
+            creatorFeePercentage: new BN(0),
+          },
+          true
+        )
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          curveTemplate: curveTemplatePda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      const reconfigure = async (changes: any) => {
+        const config = await program.account.config.fetch(configPda);
+        await program.methods
+          .configure({ ...config, ...changes })
+          .accounts({
+            admin: creator.publicKey,
+            globalConfig: configPda,
+            pendingConfig: pendingConfigPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+        await executeConfig();
+      };
+
+      type Launched = {
+        mint: PublicKey;
+        curve: PublicKey;
+        curveTokenAccount: PublicKey;
+      };
+
+      // Launch a curve and complete it: a buy crossing the limit fills up to it
+      const launchCompletedCurve = async (): Promise<Launched> => {
+        const mint = Keypair.generate();
+        const [curve] = PublicKey.findProgramAddressSync(
+          [Buffer.from("bonding_curve"), mint.publicKey.toBuffer()],
+          program.programId
+        );
+        const curveTokenAccount = await getAssociatedTokenAddress(
+          mint.publicKey,
+          curve,
+          true
+        );
+
+        await program.methods
+          .launch(
+            "Swifey Pool",
+            "SWFP",
+            "https://swifey.io/metadata.json",
+            templateIndex
+          )
+          .accounts({
+            creator: creator.publicKey,
+            globalConfig: configPda,
+            curveTemplate: curveTemplatePda,
+            tokenMint: mint.publicKey,
+            bondingCurve: curve,
+            curveTokenAccount: curveTokenAccount,
+            tokenMetadataAccount: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("metadata"),
+                METADATA_PROGRAM_ID.toBuffer(),
+                mint.publicKey.toBuffer(),
+              ],
+              METADATA_PROGRAM_ID
+            )[0],
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            metadataProgram: METADATA_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([creator, mint])
+          .rpc();
+
+        await program.methods
+          .swap(new BN(10 * anchor.web3.LAMPORTS_PER_SOL), 0, new BN(0))
+          .accounts({
+            user: keeper.publicKey,
+            globalConfig: configPda,
+            feeVault: feeVaultPda,
+            bondingCurve: curve,
+            tokenMint: mint.publicKey,
+            curveTokenAccount: curveTokenAccount,
+            userTokenAccount: await getAssociatedTokenAddress(
+              mint.publicKey,
+              keeper.publicKey
+            ),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([keeper])
+          .preInstructions([computeBudget])
+          .rpc();
+
+        const bondingCurve = await program.account.bondingCurve.fetch(curve);
+        expect(bondingCurve.isCompleted).to.be.true;
+        return { mint: mint.publicKey, curve, curveTokenAccount };
+      };
+
+      // Raydium orders the pool mints by key
+      const poolMints = (mint: PublicKey) =>
+        Buffer.compare(wsolMint.toBuffer(), mint.toBuffer()) < 0
+          ? [wsolMint, mint]
+          : [mint, wsolMint];
+
+      const commonAccounts = async (launched: Launched, lpMint: PublicKey) => ({
+        keeper: keeper.publicKey,
+        config: configPda,
+        bondingCurve: launched.curve,
+        tokenMint: launched.mint,
+        wsolMint: wsolMint,
+        curveTokenAccount: launched.curveTokenAccount,
+        curveWsolAccount: await getAssociatedTokenAddress(
+          wsolMint,
+          launched.curve,
+          true
+        ),
+        lpLock: lpLockPda,
+        lpLockAccount: await getAssociatedTokenAddress(lpMint, lpLockPda, true),
+        feeVault: feeVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      });
+
+      // Opens a full-range position, the position NFT stands for the liquidity
+      const migrateClmm = async (launched: Launched) => {
+        const [mint0, mint1] = poolMints(launched.mint);
+        const pda = (seeds: Buffer[]) =>
+          PublicKey.findProgramAddressSync(seeds, CLMM_PROGRAM_ID)[0];
+        const i32 = (value: number) => {
+          const buffer = Buffer.alloc(4);
+          buffer.writeInt32BE(value);
+          return buffer;
+        };
+
+        // tick_spacing follows bump, index, owner and the two fee rates in the AMM config
+        const tickSpacing = (
+          await provider.connection.getAccountInfo(ammConfig)
+        ).data.readUInt16LE(8 + 1 + 2 + 32 + 4 + 4);
+        const tickUpper = Math.trunc(443636 / tickSpacing) * tickSpacing;
+        const tickLower = -tickUpper;
+        const tickArrayStart = (tick: number) =>
+          Math.floor(tick / (60 * tickSpacing)) * 60 * tickSpacing;
+
+        const poolState = pda([
+          Buffer.from("pool"),
+          ammConfig.toBuffer(),
+          mint0.toBuffer(),
+          mint1.toBuffer(),
+        ]);
+        const positionNftMint = Keypair.generate();
+
+        await program.methods
+          .migrateClmm()
+          .accounts({
+            common: await commonAccounts(launched, positionNftMint.publicKey),
+            poolState: poolState,
+            observationState: pda([
+              Buffer.from("observation"),
+              poolState.toBuffer(),
+            ]),
+            tokenVault0: pda([
+              Buffer.from("pool_vault"),
+              poolState.toBuffer(),
+              mint0.toBuffer(),
+            ]),
+            tokenVault1: pda([
+              Buffer.from("pool_vault"),
+              poolState.toBuffer(),
+              mint1.toBuffer(),
+            ]),
+            tickArrayBitmap: pda([
+              Buffer.from("pool_tick_array_bitmap_extension"),
+              poolState.toBuffer(),
+            ]),
+            tickArrayLower: pda([
+              Buffer.from("tick_array"),
+              poolState.toBuffer(),
+              i32(tickArrayStart(tickLower)),
+            ]),
+            tickArrayUpper: pda([
+              Buffer.from("tick_array"),
+              poolState.toBuffer(),
+              i32(tickArrayStart(tickUpper)),
+            ]),
+            positionNftMint: positionNftMint.publicKey,
+            positionNftAccount: await getAssociatedTokenAddress(
+              positionNftMint.publicKey,
+              launched.curve,
+              true
+            ),
+            metadataAccount: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("metadata"),
+                METADATA_PROGRAM_ID.toBuffer(),
+                positionNftMint.publicKey.toBuffer(),
+              ],
+              METADATA_PROGRAM_ID
+            )[0],
+            protocolPosition: pda([
+              Buffer.from("position"),
+              poolState.toBuffer(),
+              i32(tickLower),
+              i32(tickUpper),
+            ]),
+            personalPosition: pda([
+              Buffer.from("position"),
+              positionNftMint.publicKey.toBuffer(),
+            ]),
+            ammConfig: ammConfig,
+            metadataProgram: METADATA_PROGRAM_ID,
+            raydiumProgram: CLMM_PROGRAM_ID,
+          })
+          .signers([keeper, positionNftMint])
+          .preInstructions([computeBudget])
+          .rpc();
+
+        return { pool: poolState, lpMint: positionNftMint.publicKey };
+      };
+
+      // Creates the pool under a fresh keypair, the LP token stands for the liquidity
+      const migrateCpmm = async (launched: Launched) => {
+        const [mint0, mint1] = poolMints(launched.mint);
+        const pda = (seeds: Buffer[]) =>
+          PublicKey.findProgramAddressSync(seeds, CPMM_PROGRAM_ID)[0];
+        const poolState = Keypair.generate();
+        const lpMint = pda([
+          Buffer.from("pool_lp_mint"),
+          poolState.publicKey.toBuffer(),
+        ]);
+
+        await program.methods
+          .migrateCpmm()
+          .accounts({
+            common: await commonAccounts(launched, lpMint),
+            poolAuthority: pda([Buffer.from("vault_and_lp_mint_auth_seed")]),
+            poolState: poolState.publicKey,
+            lpMint: lpMint,
+            keeperWsolAccount: await getAssociatedTokenAddress(
+              wsolMint,
+              keeper.publicKey
+            ),
+            keeperTokenAccount: await getAssociatedTokenAddress(
+              launched.mint,
+              keeper.publicKey
+            ),
+            keeperLpAccount: await getAssociatedTokenAddress(
+              lpMint,
+              keeper.publicKey
+            ),
+            tokenVault0: pda([
+              Buffer.from("pool_vault"),
+              poolState.publicKey.toBuffer(),
+              mint0.toBuffer(),
+            ]),
+            tokenVault1: pda([
+              Buffer.from("pool_vault"),
+              poolState.publicKey.toBuffer(),
+              mint1.toBuffer(),
+            ]),
+            createPoolFee: CPMM_CREATE_POOL_FEE_RECEIVER,
+            observationState: pda([
+              Buffer.from("observation"),
+              poolState.publicKey.toBuffer(),
+            ]),
+            ammConfig: cpmmAmmConfig,
+            raydiumProgram: CPMM_PROGRAM_ID,
+          })
+          .signers([keeper, poolState])
+          .preInstructions([computeBudget])
+          .rpc();
+
+        return { pool: poolState.publicKey, lpMint };
+      };
+
+      const original = await program.account.config.fetch(configPda);
+      try {
+        for (const migrationTarget of [{ raydiumClmm: {} }, { raydiumCpmm: {} }]) {
+          for (const lpPolicy of [{ lock: {} }, { burn: {} }]) {
+            // The target is frozen on the curve at launch, the policy is read at migration
+            await reconfigure({
+              migrationTarget,
+              lpPolicy,
+              keeperRewardPercentage: new BN(5000), // 50% of the migration fee
+            });
+            const launched = await launchCompletedCurve();
+            const { pool, lpMint } =
+              "raydiumClmm" in migrationTarget
+                ? await migrateClmm(launched)
+                : await migrateCpmm(launched);
+
+            const bondingCurve = await program.account.bondingCurve.fetch(
+              launched.curve
+            );
+            expect(bondingCurve.isMigrated).to.be.true;
+            expect(bondingCurve.realSolReserve.toNumber()).to.equal(0);
+            expect(bondingCurve.realTokenReserve.toNumber()).to.equal(0);
+            expect(
+              (await provider.connection.getAccountInfo(pool)).owner.toString()
+            ).to.equal(
+              ("raydiumClmm" in migrationTarget
+                ? CLMM_PROGRAM_ID
+                : CPMM_PROGRAM_ID
+              ).toString()
+            );
+
+            // Locked liquidity sits in the LP lock, burned liquidity is gone for good
+            const lpSupply = (await provider.connection.getTokenSupply(lpMint))
+              .value.amount;
+            const lpLockAccount = await getAssociatedTokenAddress(
+              lpMint,
+              lpLockPda,
+              true
+            );
+            if ("lock" in lpPolicy) {
+              expect(lpSupply).to.not.equal("0");
+              const locked = await provider.connection.getTokenAccountBalance(
+                lpLockAccount
+              );
+              expect(locked.value.amount).to.equal(lpSupply);
+            } else {
+              expect(lpSupply).to.equal("0");
+              expect(await provider.connection.getAccountInfo(lpLockAccount)).to
+                .be.null;
+            }
+          }
+        }
+      } finally {
+        await reconfigure({
+          migrationTarget: original.migrationTarget,
+          lpPolicy: original.lpPolicy,
+          keeperRewardPercentage: original.keeperRewardPercentage,
+        });
+      }
+    });
+
+    // Raises the timelock, so it runs last
+    it("Holds queued config changes until the timelock expires", async () => {
+      const config = await program.account.config.fetch(configPda);
+      const queue = (configTimelock: BN) =>
+        program.methods
+          .configure({ ...config, configTimelock })
+          .accounts({
+            admin: creator.publicKey,
+            globalConfig: configPda,
+            pendingConfig: pendingConfigPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+      await queue(new BN(3600));
+      await executeConfig();
+      expect(
+        (await program.account.config.fetch(configPda)).configTimelock.toNumber()
+      ).to.equal(3600);
+
+      // The next change waits an hour, so it can only be cancelled for now
+      await queue(new BN(CONFIG_TIMELOCK));
+      try {
+        await executeConfig();
+        assert.fail("Should have failed before the timelock expired");
+      } catch (error) {
+        expect(error.toString()).to.include("ConfigTimelockActive");
+      }
+
+      await program.methods
+        .cancelConfig()
+        .accounts({
+          authority: creator.publicKey,
+          globalConfig: configPda,
+          pendingConfig: pendingConfigPda,
+          proposer: creator.publicKey,
+        })
+        .signers([creator])
+        .rpc();
+      expect(await provider.connection.getAccountInfo(pendingConfigPda)).to.be
+        .null;
+    });
   });
 });